@@ -0,0 +1,487 @@
+// ベンチマークプログラム共通のコマンドライン引数
+//
+// 反復回数・ウォームアップ・入力サイズ・バックエンド・アルゴリズム・出力形式を実行時に指定できるようにします。
+// 選択できるバックエンドやアルゴリズム、計測の既定値はプログラム（やモード）ごとに異なるため、
+// `CliSpec` で渡してもらい、解析時に存在しない名前や意味のない組み合わせ、
+// そのモードでは使われないオプションをエラーにします。
+
+use std::time::Duration;
+
+use crate::output::OutputFormat;
+use crate::runner::{BenchConfig, Warmup};
+
+/// `--quick` の有無で切り替わる既定値
+#[derive(Clone, Debug, PartialEq)]
+pub struct Defaults<T> {
+    /// 通常の既定値
+    pub normal: T,
+    /// `--quick` を指定した場合の既定値
+    pub quick: T,
+}
+
+impl<T: Clone> Defaults<T> {
+    /// `--quick` の有無によらず同じ既定値
+    pub fn same(value: T) -> Self {
+        Defaults { normal: value.clone(), quick: value }
+    }
+
+    fn pick(&self, quick: bool) -> T {
+        if quick { self.quick.clone() } else { self.normal.clone() }
+    }
+}
+
+impl Default for Defaults<BenchConfig> {
+    /// 圧縮関数のベンチマークの設定（`BenchConfig::default` と `BenchConfig::quick`）
+    fn default() -> Self {
+        Defaults { normal: BenchConfig::default(), quick: BenchConfig::quick() }
+    }
+}
+
+/// プログラム（やモード）ごとに異なる、引数の検証に必要な情報
+pub struct CliSpec<'a> {
+    /// 圧縮関数の1ブロックのバイト数（入力サイズはこの倍数である必要がある）
+    pub block_size: usize,
+    /// 選択できるアルゴリズム名（先頭が既定値）
+    pub algorithms: &'a [&'a str],
+    /// このビルドに含まれるバックエンドの名前と、実行中のCPUで利用できるかどうか
+    pub backends: &'a [(&'a str, bool)],
+    /// `--backend` を省略したときに使用するバックエンド（"all" の場合は利用可能なすべて）
+    pub default_backend: &'a str,
+    /// 計測の設定の既定値
+    pub config: Defaults<BenchConfig>,
+    /// `--size` の既定値（`None` の場合、`--size` は指定できない）
+    pub size: Option<Defaults<usize>>,
+}
+
+/// 解析したベンチマークの設定
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchOptions<'a> {
+    /// 計測の設定
+    pub config: BenchConfig,
+    /// 入力のバイト数（ブロックサイズの倍数、`--size` を指定できない場合は0）
+    pub input_size: usize,
+    /// 使用するアルゴリズム（`CliSpec::algorithms` のいずれか）
+    pub algorithm: &'a str,
+    /// 計測するバックエンド（指定順、重複なし）
+    pub backends: Vec<&'a str>,
+    /// 結果の出力形式
+    pub format: OutputFormat,
+}
+
+/// 引数解析の結果
+#[derive(Debug, PartialEq)]
+pub enum CliCommand<'a> {
+    Run(BenchOptions<'a>),
+    Help,
+}
+
+/// コマンドライン引数（プログラム名を除く）を解析する
+/// 値を取るオプションは `--samples 100` と `--samples=100` のどちらの形式でも指定できます。
+/// `--quick` は既定値を短い設定に置き換えるだけで、個別に指定した値が優先されます。
+pub fn parse_args<'a>(
+    args: impl IntoIterator<Item = String>,
+    spec: &CliSpec<'a>,
+) -> Result<CliCommand<'a>, String> {
+    let mut quick = false;
+    let mut samples: Option<usize> = None;
+    let mut iterations: Option<usize> = None;
+    let mut warmup: Option<usize> = None;
+    let mut warmup_time: Option<Duration> = None;
+    let mut input_size: Option<usize> = None;
+    let mut algorithm: Option<&'a str> = None;
+    let mut backends: Vec<&'a str> = Vec::new();
+    let mut all_backends = false;
+    let mut format: Option<OutputFormat> = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+
+        // 値を取らないオプション
+        match name.as_str() {
+            "-h" | "--help" | "--quick" => {
+                if inline_value.is_some() {
+                    return Err(format!("{} は値を取りません", name));
+                }
+                if name == "--quick" {
+                    quick = true;
+                    continue;
+                }
+                return Ok(CliCommand::Help);
+            }
+            "--samples" | "--iterations" | "--warmup" | "--warmup-time" | "--size"
+            | "--backend" | "--algorithm" | "--format" => {}
+            _ if name.starts_with('-') => return Err(format!("不明なオプション '{}'", name)),
+            _ => return Err(format!("余分な引数 '{}'", arg)),
+        }
+
+        let value = match inline_value.or_else(|| args.next()) {
+            Some(value) => value,
+            None => return Err(format!("{} には値が必要です", name)),
+        };
+        match name.as_str() {
+            "--samples" => set_once(&mut samples, &name, parse_count(&name, &value, 1)?)?,
+            "--iterations" => set_once(&mut iterations, &name, parse_count(&name, &value, 1)?)?,
+            "--warmup" => set_once(&mut warmup, &name, parse_count(&name, &value, 0)?)?,
+            "--warmup-time" => set_once(&mut warmup_time, &name, parse_seconds(&name, &value)?)?,
+            "--size" => {
+                if spec.size.is_none() {
+                    return Err("--size はこのモードでは指定できません".to_string());
+                }
+                set_once(&mut input_size, &name, parse_size(&value, spec.block_size)?)?
+            }
+            "--algorithm" => {
+                let found = spec.algorithms.iter().copied().find(|&a| a == value);
+                let found = found.ok_or_else(|| {
+                    format!("不明なアルゴリズム '{}'（選択可能: {}）", value, spec.algorithms.join(", "))
+                })?;
+                set_once(&mut algorithm, &name, found)?;
+            }
+            "--format" => {
+                let found = OutputFormat::from_name(&value).ok_or_else(|| {
+                    format!("不明な出力形式 '{}'（選択可能: {}）", value, OutputFormat::NAMES.join(", "))
+                })?;
+                set_once(&mut format, &name, found)?;
+            }
+            "--backend" => {
+                for item in value.split(',') {
+                    if item == "all" {
+                        all_backends = true;
+                    } else {
+                        let backend = find_backend(spec, item)?;
+                        if backends.contains(&backend) {
+                            return Err(format!("バックエンド '{}' が重複して指定されています", item));
+                        }
+                        backends.push(backend);
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    if warmup.is_some() && warmup_time.is_some() {
+        return Err("--warmup と --warmup-time は同時に指定できません".to_string());
+    }
+    if backends.is_empty() && !all_backends {
+        if spec.default_backend == "all" {
+            all_backends = true;
+        } else {
+            backends.push(find_backend(spec, spec.default_backend)?);
+        }
+    }
+    if all_backends {
+        if !backends.is_empty() {
+            return Err("--backend all と個別のバックエンドは同時に指定できません".to_string());
+        }
+        backends = spec
+            .backends
+            .iter()
+            .filter(|(_, available)| *available)
+            .map(|&(name, _)| name)
+            .collect();
+    }
+
+    let mut config = spec.config.pick(quick);
+    if let Some(n) = warmup {
+        config.warmup = Warmup::Iterations(n);
+    }
+    if let Some(duration) = warmup_time {
+        config.warmup = Warmup::Duration(duration);
+    }
+    config.samples = samples.unwrap_or(config.samples);
+    config.iterations_per_sample = iterations.unwrap_or(config.iterations_per_sample);
+
+    Ok(CliCommand::Run(BenchOptions {
+        config,
+        input_size: input_size.or_else(|| spec.size.as_ref().map(|size| size.pick(quick))).unwrap_or(0),
+        algorithm: algorithm.unwrap_or(spec.algorithms[0]),
+        backends,
+        format: format.unwrap_or(OutputFormat::Human),
+    }))
+}
+
+/// 同じオプションが2回以上指定されていないことを確認して値を設定する
+fn set_once<T>(slot: &mut Option<T>, name: &str, value: T) -> Result<(), String> {
+    if slot.is_some() {
+        return Err(format!("{} が複数回指定されています", name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// `min` 以上の整数を解析する
+fn parse_count(name: &str, value: &str, min: usize) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!("{} には{}以上の整数を指定してください: '{}'", name, min, value)),
+    }
+}
+
+/// 0以上の秒数（小数可）を解析する
+fn parse_seconds(name: &str, value: &str) -> Result<Duration, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("{} には0以上の秒数を指定してください: '{}'", name, value))
+}
+
+/// 入力サイズを解析する（K / M 接尾辞は1024倍 / 1048576倍）
+fn parse_size(value: &str, block_size: usize) -> Result<usize, String> {
+    let (digits, multiplier) = match value.strip_suffix(['K', 'k']) {
+        Some(digits) => (digits, 1024),
+        None => match value.strip_suffix(['M', 'm']) {
+            Some(digits) => (digits, 1024 * 1024),
+            None => (value, 1),
+        },
+    };
+    let size = digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|&size| size > 0)
+        .ok_or_else(|| format!("--size には1以上のバイト数を指定してください: '{}'", value))?;
+    if !size.is_multiple_of(block_size) {
+        return Err(format!(
+            "--size はブロックサイズ（{}バイト）の倍数で指定してください: {}",
+            block_size, size
+        ));
+    }
+    Ok(size)
+}
+
+/// バックエンド名を検索し、実行中のCPUで利用できることを確認する
+fn find_backend<'a>(spec: &CliSpec<'a>, name: &str) -> Result<&'a str, String> {
+    match spec.backends.iter().find(|&&(backend, _)| backend == name) {
+        Some(&(backend, true)) => Ok(backend),
+        Some(_) => Err(format!("バックエンド '{}' はこのCPUでは利用できません", name)),
+        None => {
+            let names: Vec<&str> = spec.backends.iter().map(|&(backend, _)| backend).collect();
+            Err(format!("不明なバックエンド '{}'（選択可能: {}, all）", name, names.join(", ")))
+        }
+    }
+}
+
+/// オプションの説明を表示（`prog` にはモード名を含めて渡す）
+pub fn print_help(prog: &str, spec: &CliSpec) {
+    let defaults = &spec.config.normal;
+    let quick = &spec.config.quick;
+    println!("使い方: {} [オプション]...", prog);
+    println!();
+    println!("  --samples N        計測するサンプル数（既定: {}）", defaults.samples);
+    println!("  --iterations N     1サンプルあたりの呼び出し回数（既定: {}）", defaults.iterations_per_sample);
+    println!("  --warmup N         ウォームアップの呼び出し回数（既定: {}）", defaults.warmup);
+    println!("  --warmup-time SEC  ウォームアップを回数ではなく秒数で指定");
+    if let Some(size) = &spec.size {
+        println!(
+            "  --size BYTES       入力のバイト数（{}の倍数、K/M接尾辞可、既定: {}）",
+            spec.block_size,
+            format_size(size.normal)
+        );
+    }
+    println!("  --backend NAME     計測するバックエンド（カンマ区切りで複数可、all で利用可能なすべて）");
+    println!("  --algorithm NAME   アルゴリズム（既定: {}）", spec.algorithms[0]);
+    println!("  --format FORMAT    結果の出力形式: {}（既定: human）", OutputFormat::NAMES.join(", "));
+    let quick_size = match &spec.size {
+        Some(size) if size.quick != size.normal => format!("、入力 {}", format_size(size.quick)),
+        _ => String::new(),
+    };
+    println!(
+        "  --quick            短い設定で計測（ウォームアップ {}、{} サンプル × {} 回{}）",
+        quick.warmup, quick.samples, quick.iterations_per_sample, quick_size
+    );
+    println!("  -h, --help         この説明を表示");
+    println!();
+    let backends: Vec<String> = spec
+        .backends
+        .iter()
+        .map(|&(name, available)| if available { name.to_string() } else { format!("{}（利用不可）", name) })
+        .collect();
+    println!("バックエンド: {}（既定: {}）", backends.join(", "), spec.default_backend);
+    println!("アルゴリズム: {}", spec.algorithms.join(", "));
+}
+
+/// バイト数の表示（`--size` と同じ K / M 接尾辞）
+fn format_size(size: usize) -> String {
+    if size >= 1024 * 1024 && size.is_multiple_of(1024 * 1024) {
+        format!("{}M", size / (1024 * 1024))
+    } else if size >= 1024 && size.is_multiple_of(1024) {
+        format!("{}K", size / 1024)
+    } else {
+        size.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> CliSpec<'static> {
+        CliSpec {
+            block_size: 64,
+            algorithms: &["sha256", "sha224"],
+            backends: &[("fast", false), ("simd", true), ("generic", true)],
+            default_backend: "generic",
+            config: Defaults::default(),
+            size: Some(Defaults::same(64)),
+        }
+    }
+
+    fn parse(args: &[&str]) -> Result<CliCommand<'static>, String> {
+        parse_with(&spec(), args)
+    }
+
+    fn parse_with(spec: &CliSpec<'static>, args: &[&str]) -> Result<CliCommand<'static>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()), spec)
+    }
+
+    fn run(args: &[&str]) -> BenchOptions<'static> {
+        match parse(args) {
+            Ok(CliCommand::Run(options)) => options,
+            other => panic!("{:?}: {:?}", args, other),
+        }
+    }
+
+    #[test]
+    fn test_defaults() {
+        let options = run(&[]);
+        assert_eq!(options.config, BenchConfig::default());
+        assert_eq!(options.input_size, 64);
+        assert_eq!(options.algorithm, "sha256");
+        assert_eq!(options.backends, ["generic"]);
+        assert_eq!(options.format, OutputFormat::Human);
+    }
+
+    #[test]
+    fn test_all_options() {
+        let options = run(&[
+            "--samples", "10", "--iterations=20", "--warmup-time", "0.5",
+            "--size", "1K", "--algorithm=sha224", "--backend", "simd,generic", "--format=md",
+        ]);
+        assert_eq!(
+            options.config,
+            BenchConfig {
+                warmup: Warmup::Duration(Duration::from_millis(500)),
+                samples: 10,
+                iterations_per_sample: 20,
+            }
+        );
+        assert_eq!(options.input_size, 1024);
+        assert_eq!(options.algorithm, "sha224");
+        assert_eq!(options.backends, ["simd", "generic"]);
+        assert_eq!(options.format, OutputFormat::Markdown);
+    }
+
+    // --quick は既定値だけを置き換え、個別の指定が優先されること（順序によらない）
+    #[test]
+    fn test_quick_profile() {
+        assert_eq!(run(&["--quick"]).config, BenchConfig::quick());
+        let options = run(&["--samples", "7", "--quick", "--warmup", "0"]);
+        assert_eq!(options.config.samples, 7);
+        assert_eq!(options.config.iterations_per_sample, BenchConfig::quick().iterations_per_sample);
+        assert_eq!(options.config.warmup, Warmup::Iterations(0));
+    }
+
+    // all は利用可能なバックエンドだけに展開されること
+    #[test]
+    fn test_backend_all() {
+        assert_eq!(run(&["--backend", "all"]).backends, ["simd", "generic"]);
+    }
+
+    // モードごとの既定値: 既定のバックエンド "all"、--quick で切り替わる設定と入力サイズ
+    #[test]
+    fn test_mode_defaults() {
+        let quick = BenchConfig { warmup: Warmup::Iterations(1), samples: 3, iterations_per_sample: 1 };
+        let spec = CliSpec {
+            default_backend: "all",
+            config: Defaults { normal: BenchConfig::default(), quick: quick.clone() },
+            size: Some(Defaults { normal: 16 * 1024 * 1024, quick: 1024 * 1024 }),
+            ..spec()
+        };
+        let run_with = |args: &[&str]| match parse_with(&spec, args) {
+            Ok(CliCommand::Run(options)) => options,
+            other => panic!("{:?}: {:?}", args, other),
+        };
+        let options = run_with(&[]);
+        assert_eq!(options.backends, ["simd", "generic"]);
+        assert_eq!((options.config, options.input_size), (BenchConfig::default(), 16 * 1024 * 1024));
+        let options = run_with(&["--quick"]);
+        assert_eq!((options.config, options.input_size), (quick, 1024 * 1024));
+        assert_eq!(run_with(&["--quick", "--size", "4K"]).input_size, 4096);
+        assert_eq!(run_with(&["--backend", "generic"]).backends, ["generic"]);
+    }
+
+    // --size を受け付けないモードでは、指定するとエラーになること
+    #[test]
+    fn test_size_not_supported() {
+        let spec = CliSpec { size: None, ..spec() };
+        assert_eq!(
+            parse_with(&spec, &["--size", "64"]),
+            Err("--size はこのモードでは指定できません".to_string())
+        );
+        match parse_with(&spec, &[]) {
+            Ok(CliCommand::Run(options)) => assert_eq!(options.input_size, 0),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(64), "64");
+        assert_eq!(format_size(65536), "64K");
+        assert_eq!(format_size(64 * 1024 * 1024), "64M");
+        assert_eq!(format_size(1536), "1536");
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(parse(&["--samples", "3", "--help"]), Ok(CliCommand::Help));
+        assert_eq!(parse(&["-h"]), Ok(CliCommand::Help));
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let cases: &[&[&str]] = &[
+            &["--samples", "0"],
+            &["--iterations", "x"],
+            &["--warmup", "-1"],
+            &["--warmup-time", "-1"],
+            &["--warmup-time", "NaN"],
+            &["--samples"],
+            &["--samples", "1", "--samples", "2"],
+            &["--warmup", "10", "--warmup-time", "1"],
+            &["--size", "0"],
+            &["--size", "100"],
+            &["--size", "99999999999999999999M"],
+            &["--algorithm", "md5"],
+            &["--backend", "unknown"],
+            &["--backend", "fast"],
+            &["--backend", "simd,simd"],
+            &["--backend", "all,generic"],
+            &["--format", "xml"],
+            &["--format", "json", "--format", "csv"],
+            &["--quick=1"],
+            &["--verbose"],
+            &["extra"],
+        ];
+        for args in cases {
+            assert!(parse(args).is_err(), "{:?} はエラーになるべき", args);
+        }
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            parse(&["--backend", "fast"]),
+            Err("バックエンド 'fast' はこのCPUでは利用できません".to_string())
+        );
+        assert_eq!(
+            parse(&["--size", "96"]),
+            Err("--size はブロックサイズ（64バイト）の倍数で指定してください: 96".to_string())
+        );
+    }
+}
@@ -0,0 +1,22 @@
+// SHA-256 / SHA-512 のベンチマークプログラムで共通に使用する計測ハーネス
+//
+// 各ベンチマークプログラムはこれまで、ウォームアップのループ、ITERATIONS_1 × ITERATIONS_2 の
+// 計測ループ、統計計算をそれぞれ個別に実装していました。このクレートはそれらをまとめたもので、
+// 計測したい処理をクロージャとして `Harness` に登録すると、同じ手順で計測して
+// 構造化された結果（`BenchResult`）を返します。
+// 計測の設定はコマンドライン引数（`parse_args`）から指定でき、結果は従来の表示のほか
+// JSON・CSV・Markdown（`write_report`）でも出力できます。
+// x86-64 と AArch64 では、壁時計の時間に加えてサイクルカウンタ（`CycleCounter`）で計測した
+// 1バイト・1ブロックあたりのサイクル数も出力します。
+
+mod cli;
+mod output;
+mod runner;
+mod stats;
+mod timer;
+
+pub use cli::{parse_args, print_help, BenchOptions, CliCommand, CliSpec, Defaults};
+pub use output::{write_report, OutputFormat, RunMetadata, JSON_SCHEMA_VERSION};
+pub use runner::{run_bench, BenchConfig, BenchResult, Harness, Warmup};
+pub use stats::Stats;
+pub use timer::CycleCounter;
@@ -0,0 +1,622 @@
+// 計測結果の出力形式（人が読むための表示と、集計ツール向けの JSON / CSV / Markdown）
+//
+// JSON の形式（`schema_version` = 1）:
+//
+//   {
+//     "schema_version": 1,
+//     "metadata": {
+//       "program": 文字列,            実行したプログラム名
+//       "harness_version": 文字列,    bench_harness のバージョン
+//       "algorithm": 文字列,          "sha256" / "sha512/256" など
+//       "input_size": 整数,           --size で指定した入力のバイト数（指定できないモードでは0）
+//       "block_size": 整数,           圧縮関数の1ブロックのバイト数
+//       "timestamp": 整数,            計測終了時刻（UNIX時間、秒）
+//       "os": 文字列, "arch": 文字列,  ビルド対象の OS と CPU アーキテクチャ
+//       "cycle_counter": {             サイクル数の計測に使用したカウンタ（使用できない場合は null）
+//         "name": 文字列,                "rdtscp" / "rdtsc" / "cntvct_el0"
+//         "frequency_hz": 数値,          壁時計で較正したカウンタの周波数
+//         "core_frequency_hz": 数値 または null,  推定したコアの動作周波数
+//         "differs_from_core_frequency": 真偽値  true の場合、サイクル数はコアのサイクル数と一致しない
+//       }
+//     },
+//     "results": [                    登録順、バックエンドごとに1要素
+//       {
+//         "name": 文字列,
+//         "bytes_per_iteration": 整数,
+//         "warmup": {"iterations": 整数} または {"seconds": 数値},
+//         "samples": 整数,              サンプル数
+//         "iterations_per_sample": 整数,
+//         "sample_ns": {                1サンプル（iterations_per_sample 回の呼び出し）の時間
+//           "mean", "median", "std_dev", "min", "p5", "p25", "p75", "p95", "p99", "max": 数値
+//         },
+//         "per_call_ns": 数値,          1回の呼び出しあたりの平均時間
+//         "throughput_mib_per_sec": 数値 または null（処理バイト数が0の場合）
+//         "per_call_cycles": 数値 または null,   1回の呼び出しあたりの平均サイクル数（カウンタのサイクル）
+//         "cycles_per_byte": 数値 または null,
+//         "cycles_per_block": 数値 または null
+//       }
+//     ]
+//   }
+//
+// CSV は1行目が列名で、結果1件につき1行です（列は `CSV_COLUMNS` の順、メタデータも各行に含む）。
+// 非数（NaN・無限大）は JSON では null、CSV では空欄になります。
+// サイクル数はカウンタのサイクル数で、カウンタの周波数がコアの動作周波数と異なる場合は、
+// 従来の表示と Markdown ではその旨の注意を出力します。
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::runner::{BenchResult, Warmup};
+use crate::timer::CycleCounter;
+
+/// JSON 出力の形式のバージョン（互換性のない変更をしたときに増やす）
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// CSV の列名
+const CSV_COLUMNS: &[&str] = &[
+    "program",
+    "algorithm",
+    "input_size",
+    "timestamp",
+    "os",
+    "arch",
+    "name",
+    "bytes_per_iteration",
+    "warmup_iterations",
+    "warmup_seconds",
+    "samples",
+    "iterations_per_sample",
+    "mean_ns",
+    "median_ns",
+    "std_dev_ns",
+    "min_ns",
+    "p5_ns",
+    "p25_ns",
+    "p75_ns",
+    "p95_ns",
+    "p99_ns",
+    "max_ns",
+    "per_call_ns",
+    "throughput_mib_per_sec",
+    "block_size",
+    "cycle_counter",
+    "counter_frequency_hz",
+    "core_frequency_hz",
+    "per_call_cycles",
+    "cycles_per_byte",
+    "cycles_per_block",
+];
+
+/// 結果の出力形式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 従来どおりの日本語の表示（既定）
+    Human,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl OutputFormat {
+    /// `--format` で指定できる名前
+    pub const NAMES: &'static [&'static str] = &["human", "json", "csv", "markdown"];
+
+    /// 名前から出力形式を得る（"md" は "markdown" の別名）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(OutputFormat::Human),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            "markdown" | "md" => Some(OutputFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// 計測全体に共通する情報
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunMetadata {
+    pub program: String,
+    pub algorithm: String,
+    pub input_size: usize,
+    /// 圧縮関数の1ブロックのバイト数（1ブロックあたりのサイクル数の計算に使用）
+    pub block_size: usize,
+    /// UNIX時間（秒）
+    pub timestamp: u64,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// サイクル数の計測に使用したカウンタ
+    pub counter: Option<CycleCounter>,
+}
+
+impl RunMetadata {
+    /// 現在時刻と、ビルド対象の OS・アーキテクチャ、サイクルカウンタを設定して作成する
+    pub fn new(program: &str, algorithm: &str, input_size: usize, block_size: usize) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        RunMetadata {
+            program: program.to_string(),
+            algorithm: algorithm.to_string(),
+            input_size,
+            block_size,
+            timestamp,
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            counter: CycleCounter::detect(),
+        }
+    }
+}
+
+/// 結果を指定した形式で `out` に書き出す
+pub fn write_report(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    metadata: &RunMetadata,
+    results: &[BenchResult],
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Human => {
+            for (i, result) in results.iter().enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                write_human(out, result, Some(metadata.block_size))?;
+            }
+            write_counter_note(out, metadata.counter.as_ref())
+        }
+        OutputFormat::Json => write_json(out, metadata, results),
+        OutputFormat::Csv => write_csv(out, metadata, results),
+        OutputFormat::Markdown => write_markdown(out, metadata, results),
+    }
+}
+
+/// 従来のベンチマークプログラムと同じ形式（カウンタがある場合はサイクル数を追加）
+pub(crate) fn write_human(out: &mut dyn Write, result: &BenchResult, block_size: Option<usize>) -> io::Result<()> {
+    writeln!(out, "=== 統計情報（{}: {}回の処理あたり） ===", result.name, result.config.iterations_per_sample)?;
+    writeln!(out, "平均値:   {:.12} 秒", result.stats.mean_ns / 1e9)?;
+    writeln!(out, "中央値:   {:.12} 秒", result.stats.median_ns / 1e9)?;
+    writeln!(out, "標準偏差: {:.12} 秒", result.stats.std_dev_ns / 1e9)?;
+    writeln!(out)?;
+
+    writeln!(out, "=== 1回あたりの平均実行時間 ===")?;
+    writeln!(out, "{:.12} 秒", result.per_call_ns() / 1e9)?;
+    if let Some(mib_per_sec) = result.mib_per_sec() {
+        writeln!(out, "{:.1} MiB/秒", mib_per_sec)?;
+    }
+
+    if let (Some(counter), Some(cycles_per_call)) = (result.counter, result.cycles_per_call()) {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "=== 1回あたりの平均サイクル数（{}, {}） ===",
+            counter.name(),
+            format_frequency(counter.frequency_hz())
+        )?;
+        writeln!(out, "{:.1} サイクル", cycles_per_call)?;
+        if let Some(cycles_per_byte) = result.cycles_per_byte() {
+            writeln!(out, "{:.2} サイクル/バイト", cycles_per_byte)?;
+        }
+        if let Some(cycles_per_block) = block_size.and_then(|size| result.cycles_per_block(size)) {
+            writeln!(out, "{:.1} サイクル/ブロック", cycles_per_block)?;
+        }
+    }
+    Ok(())
+}
+
+/// カウンタの周波数がコアの動作周波数と異なる場合に、サイクル数の意味についての注意を書き出す
+pub(crate) fn write_counter_note(out: &mut dyn Write, counter: Option<&CycleCounter>) -> io::Result<()> {
+    let Some(counter) = counter.filter(|counter| counter.differs_from_core()) else {
+        return Ok(());
+    };
+    writeln!(out)?;
+    match counter.core_frequency_hz() {
+        Some(core_frequency_hz) => writeln!(
+            out,
+            "注意: サイクル数はカウンタ（{}, {}）の値で、コアの動作周波数（推定 {}）とは異なります。\
+             コアのサイクル数はおよそ {:.2} 倍です。",
+            counter.name(),
+            format_frequency(counter.frequency_hz()),
+            format_frequency(core_frequency_hz),
+            core_frequency_hz / counter.frequency_hz()
+        ),
+        None => writeln!(
+            out,
+            "注意: サイクル数はカウンタ（{}, {}）の値で、コアの動作周波数と一致するかは確認できませんでした。",
+            counter.name(),
+            format_frequency(counter.frequency_hz())
+        ),
+    }
+}
+
+/// 周波数の表示（1 GHz 以上は GHz、それ未満は MHz）
+fn format_frequency(hz: f64) -> String {
+    if hz >= 1e9 {
+        format!("{:.3} GHz", hz / 1e9)
+    } else {
+        format!("{:.1} MHz", hz / 1e6)
+    }
+}
+
+fn write_json(out: &mut dyn Write, metadata: &RunMetadata, results: &[BenchResult]) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(out, "  \"schema_version\": {},", JSON_SCHEMA_VERSION)?;
+    writeln!(out, "  \"metadata\": {{")?;
+    writeln!(out, "    \"program\": {},", json_string(&metadata.program))?;
+    writeln!(out, "    \"harness_version\": {},", json_string(env!("CARGO_PKG_VERSION")))?;
+    writeln!(out, "    \"algorithm\": {},", json_string(&metadata.algorithm))?;
+    writeln!(out, "    \"input_size\": {},", metadata.input_size)?;
+    writeln!(out, "    \"block_size\": {},", metadata.block_size)?;
+    writeln!(out, "    \"timestamp\": {},", metadata.timestamp)?;
+    writeln!(out, "    \"os\": {},", json_string(metadata.os))?;
+    writeln!(out, "    \"arch\": {},", json_string(metadata.arch))?;
+    match &metadata.counter {
+        Some(counter) => {
+            writeln!(out, "    \"cycle_counter\": {{")?;
+            writeln!(out, "      \"name\": {},", json_string(counter.name()))?;
+            writeln!(out, "      \"frequency_hz\": {},", json_number(counter.frequency_hz()))?;
+            let core_frequency = counter.core_frequency_hz().map_or("null".to_string(), json_number);
+            writeln!(out, "      \"core_frequency_hz\": {},", core_frequency)?;
+            writeln!(out, "      \"differs_from_core_frequency\": {}", counter.differs_from_core())?;
+            writeln!(out, "    }}")?;
+        }
+        None => writeln!(out, "    \"cycle_counter\": null")?,
+    }
+    writeln!(out, "  }},")?;
+    writeln!(out, "  \"results\": [")?;
+    for (i, result) in results.iter().enumerate() {
+        let stats = &result.stats;
+        let warmup = match result.config.warmup {
+            Warmup::Iterations(n) => format!("{{\"iterations\": {}}}", n),
+            Warmup::Duration(d) => format!("{{\"seconds\": {}}}", json_number(d.as_secs_f64())),
+        };
+        writeln!(out, "    {{")?;
+        writeln!(out, "      \"name\": {},", json_string(&result.name))?;
+        writeln!(out, "      \"bytes_per_iteration\": {},", result.bytes_per_iteration)?;
+        writeln!(out, "      \"warmup\": {},", warmup)?;
+        writeln!(out, "      \"samples\": {},", result.config.samples)?;
+        writeln!(out, "      \"iterations_per_sample\": {},", result.config.iterations_per_sample)?;
+        writeln!(out, "      \"sample_ns\": {{")?;
+        let fields = [
+            ("mean", stats.mean_ns),
+            ("median", stats.median_ns),
+            ("std_dev", stats.std_dev_ns),
+            ("min", stats.min_ns),
+            ("p5", stats.p5_ns),
+            ("p25", stats.p25_ns),
+            ("p75", stats.p75_ns),
+            ("p95", stats.p95_ns),
+            ("p99", stats.p99_ns),
+            ("max", stats.max_ns),
+        ];
+        for (j, (key, value)) in fields.iter().enumerate() {
+            let comma = if j + 1 < fields.len() { "," } else { "" };
+            writeln!(out, "        \"{}\": {}{}", key, json_number(*value), comma)?;
+        }
+        writeln!(out, "      }},")?;
+        writeln!(out, "      \"per_call_ns\": {},", json_number(result.per_call_ns()))?;
+        let throughput = result.mib_per_sec().map_or("null".to_string(), json_number);
+        writeln!(out, "      \"throughput_mib_per_sec\": {},", throughput)?;
+        let cycles = [
+            ("per_call_cycles", result.cycles_per_call()),
+            ("cycles_per_byte", result.cycles_per_byte()),
+            ("cycles_per_block", result.cycles_per_block(metadata.block_size)),
+        ];
+        for (j, (key, value)) in cycles.iter().enumerate() {
+            let comma = if j + 1 < cycles.len() { "," } else { "" };
+            let value = value.map_or("null".to_string(), json_number);
+            writeln!(out, "      \"{}\": {}{}", key, value, comma)?;
+        }
+        writeln!(out, "    }}{}", if i + 1 < results.len() { "," } else { "" })?;
+    }
+    writeln!(out, "  ]")?;
+    writeln!(out, "}}")
+}
+
+fn write_csv(out: &mut dyn Write, metadata: &RunMetadata, results: &[BenchResult]) -> io::Result<()> {
+    writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+    for result in results {
+        let stats = &result.stats;
+        let (warmup_iterations, warmup_seconds) = match result.config.warmup {
+            Warmup::Iterations(n) => (n.to_string(), String::new()),
+            Warmup::Duration(d) => (String::new(), csv_number(d.as_secs_f64())),
+        };
+        let row = [
+            csv_field(&metadata.program),
+            csv_field(&metadata.algorithm),
+            metadata.input_size.to_string(),
+            metadata.timestamp.to_string(),
+            csv_field(metadata.os),
+            csv_field(metadata.arch),
+            csv_field(&result.name),
+            result.bytes_per_iteration.to_string(),
+            warmup_iterations,
+            warmup_seconds,
+            result.config.samples.to_string(),
+            result.config.iterations_per_sample.to_string(),
+            csv_number(stats.mean_ns),
+            csv_number(stats.median_ns),
+            csv_number(stats.std_dev_ns),
+            csv_number(stats.min_ns),
+            csv_number(stats.p5_ns),
+            csv_number(stats.p25_ns),
+            csv_number(stats.p75_ns),
+            csv_number(stats.p95_ns),
+            csv_number(stats.p99_ns),
+            csv_number(stats.max_ns),
+            csv_number(result.per_call_ns()),
+            result.mib_per_sec().map_or(String::new(), csv_number),
+            metadata.block_size.to_string(),
+            metadata.counter.map_or(String::new(), |counter| csv_field(counter.name())),
+            metadata.counter.map_or(String::new(), |counter| csv_number(counter.frequency_hz())),
+            metadata.counter.and_then(|counter| counter.core_frequency_hz()).map_or(String::new(), csv_number),
+            result.cycles_per_call().map_or(String::new(), csv_number),
+            result.cycles_per_byte().map_or(String::new(), csv_number),
+            result.cycles_per_block(metadata.block_size).map_or(String::new(), csv_number),
+        ];
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn write_markdown(out: &mut dyn Write, metadata: &RunMetadata, results: &[BenchResult]) -> io::Result<()> {
+    writeln!(
+        out,
+        "**{}** / {} / 入力 {} バイト / {}-{}{}",
+        markdown_cell(&metadata.program),
+        markdown_cell(&metadata.algorithm),
+        metadata.input_size,
+        metadata.os,
+        metadata.arch,
+        metadata.counter.map_or(String::new(), |counter| {
+            format!(" / {} {}", counter.name(), format_frequency(counter.frequency_hz()))
+        })
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "| バックエンド | 平均 (ns/サンプル) | 中央値 | 標準偏差 | p5 | p95 | p99 | 1回あたり (ns) | MiB/秒 \
+         | サイクル/バイト | サイクル/ブロック |"
+    )?;
+    writeln!(out, "|---|---:|---:|---:|---:|---:|---:|---:|---:|---:|---:|")?;
+    for result in results {
+        let stats = &result.stats;
+        writeln!(
+            out,
+            "| {} | {:.0} | {:.0} | {:.0} | {:.0} | {:.0} | {:.0} | {:.2} | {} | {} | {} |",
+            markdown_cell(&result.name),
+            stats.mean_ns,
+            stats.median_ns,
+            stats.std_dev_ns,
+            stats.p5_ns,
+            stats.p95_ns,
+            stats.p99_ns,
+            result.per_call_ns(),
+            result.mib_per_sec().map_or("-".to_string(), |v| format!("{:.1}", v)),
+            result.cycles_per_byte().map_or("-".to_string(), |v| format!("{:.2}", v)),
+            result.cycles_per_block(metadata.block_size).map_or("-".to_string(), |v| format!("{:.1}", v))
+        )?;
+    }
+    write_counter_note(out, metadata.counter.as_ref())
+}
+
+/// JSON の文字列リテラル
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// JSON の数値（非数は null）
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// CSV の数値（非数は空欄）
+fn csv_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// CSV のフィールド（区切り文字・引用符・改行を含む場合は引用符で囲む）
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Markdown の表のセル（縦線と改行をエスケープ）
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::BenchConfig;
+    use crate::stats::Stats;
+    use std::time::Duration;
+
+    fn metadata() -> RunMetadata {
+        RunMetadata {
+            program: "bench \"x\", y".to_string(),
+            algorithm: "sha512/256".to_string(),
+            input_size: 128,
+            block_size: 128,
+            timestamp: 1_700_000_000,
+            os: "linux",
+            arch: "x86_64",
+            counter: Some(counter()),
+        }
+    }
+
+    /// コアの動作周波数（3 GHz）と異なる周波数（2 GHz）のカウンタ
+    fn counter() -> CycleCounter {
+        CycleCounter::for_test(2.0e9, Some(3.0e9))
+    }
+
+    fn results() -> Vec<BenchResult> {
+        let samples_ns = vec![100, 200, 300, 400];
+        vec![
+            BenchResult {
+                name: "generic".to_string(),
+                bytes_per_iteration: 128,
+                config: BenchConfig { warmup: Warmup::Iterations(10), samples: 4, iterations_per_sample: 2 },
+                stats: Stats::from_samples(&samples_ns),
+                samples_ns,
+                counter: Some(counter()),
+                samples_cycles: vec![256; 4],
+            },
+            BenchResult {
+                name: "a|b".to_string(),
+                bytes_per_iteration: 0,
+                config: BenchConfig {
+                    warmup: Warmup::Duration(Duration::from_millis(250)),
+                    samples: 1,
+                    iterations_per_sample: 1,
+                },
+                stats: Stats::from_samples(&[50]),
+                samples_ns: vec![50],
+                counter: Some(counter()),
+                samples_cycles: vec![60],
+            },
+        ]
+    }
+
+    fn render(format: OutputFormat) -> String {
+        render_with(format, &metadata())
+    }
+
+    fn render_with(format: OutputFormat, metadata: &RunMetadata) -> String {
+        let mut out = Vec::new();
+        write_report(&mut out, format, metadata, &results()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format_names() {
+        for &name in OutputFormat::NAMES {
+            assert!(OutputFormat::from_name(name).is_some(), "{}", name);
+        }
+        assert_eq!(OutputFormat::from_name("md"), Some(OutputFormat::Markdown));
+        assert_eq!(OutputFormat::from_name("xml"), None);
+    }
+
+    #[test]
+    fn test_json() {
+        let json = render(OutputFormat::Json);
+        assert!(json.starts_with("{\n  \"schema_version\": 1,\n"));
+        assert!(json.contains("\"program\": \"bench \\\"x\\\", y\","));
+        assert!(json.contains("\"algorithm\": \"sha512/256\","));
+        assert!(json.contains("\"timestamp\": 1700000000,"));
+        assert!(json.contains("\"warmup\": {\"iterations\": 10},"));
+        assert!(json.contains("\"warmup\": {\"seconds\": 0.25},"));
+        assert!(json.contains("\"mean\": 250,"));
+        assert!(json.contains("\"median\": 250,"));
+        assert!(json.contains("\"p99\": 400,"));
+        assert!(json.contains("\"per_call_ns\": 125,"));
+        assert!(json.contains("\"throughput_mib_per_sec\": null,\n"));
+        assert!(json.contains("\"block_size\": 128,"));
+        assert!(json.contains("\"cycle_counter\": {\n      \"name\": \"test\",\n      \"frequency_hz\": 2000000000,"));
+        assert!(json.contains("\"core_frequency_hz\": 3000000000,"));
+        assert!(json.contains("\"differs_from_core_frequency\": true\n"));
+        assert!(json.contains("\"per_call_cycles\": 128,\n      \"cycles_per_byte\": 1,\n      \"cycles_per_block\": 128\n"));
+        assert!(json.contains("\"per_call_cycles\": 60,\n      \"cycles_per_byte\": null,\n"));
+        assert!(json.ends_with("    }\n  ]\n}\n"));
+        // 括弧の対応と、閉じ括弧の直前にカンマがないこと
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+        assert!(!json.contains(",\n  ]") && !json.contains(",\n      }") && !json.contains(",\n    }"));
+    }
+
+    #[test]
+    fn test_json_without_counter() {
+        let metadata = RunMetadata { counter: None, ..metadata() };
+        let json = render_with(OutputFormat::Json, &metadata);
+        assert!(json.contains("\"arch\": \"x86_64\",\n    \"cycle_counter\": null\n  },"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = render(OutputFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].split(',').count(), CSV_COLUMNS.len());
+        assert!(lines[1].starts_with(
+            "\"bench \"\"x\"\", y\",sha512/256,128,1700000000,linux,x86_64,generic,128,10,,4,2,250,250,"
+        ));
+        assert!(lines[2].contains(",a|b,0,,0.25,1,1,50,"));
+        assert!(lines[1].ends_with(",128,test,2000000000,3000000000,128,1,128"));
+        assert!(lines[2].ends_with(",50,,128,test,2000000000,3000000000,60,,"));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = render(OutputFormat::Markdown);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].ends_with(" / linux-x86_64 / test 2.000 GHz"));
+        assert!(lines[2].starts_with("| バックエンド |"));
+        assert_eq!(lines[4], "| generic | 250 | 250 | 112 | 100 | 400 | 400 | 125.00 | 976.6 | 1.00 | 128.0 |");
+        assert!(lines[5].starts_with("| a\\|b | 50 |"));
+        assert!(lines[5].ends_with("| 50.00 | - | - | - |"));
+        assert!(lines[7].starts_with("注意: "));
+    }
+
+    #[test]
+    fn test_human_is_previous_report() {
+        let human = render(OutputFormat::Human);
+        assert!(human.starts_with("=== 統計情報（generic: 2回の処理あたり） ===\n平均値:   0.000000250000 秒\n"));
+        assert!(human.contains("=== 1回あたりの平均実行時間 ===\n0.000000125000 秒\n"));
+        // 複数の結果の間は空行で区切る
+        assert!(human.contains("ブロック\n\n=== 統計情報（a|b: 1回の処理あたり） ===\n"));
+    }
+
+    #[test]
+    fn test_human_cycles() {
+        let human = render(OutputFormat::Human);
+        assert!(human.contains(
+            "=== 1回あたりの平均サイクル数（test, 2.000 GHz） ===\n\
+             128.0 サイクル\n1.00 サイクル/バイト\n128.0 サイクル/ブロック\n"
+        ));
+        assert!(human.ends_with(
+            "\n注意: サイクル数はカウンタ（test, 2.000 GHz）の値で、コアの動作周波数（推定 3.000 GHz）とは異なります。\
+             コアのサイクル数はおよそ 1.50 倍です。\n"
+        ));
+    }
+
+    // カウンタの周波数がコアの動作周波数と一致する場合は注意を出力しないこと
+    #[test]
+    fn test_no_note_when_counter_matches_core() {
+        let metadata = RunMetadata { counter: Some(CycleCounter::for_test(3.0e9, Some(3.05e9))), ..metadata() };
+        for format in [OutputFormat::Human, OutputFormat::Markdown] {
+            assert!(!render_with(format, &metadata).contains("注意"));
+        }
+    }
+
+    #[test]
+    fn test_format_frequency() {
+        assert_eq!(format_frequency(2.9954e9), "2.995 GHz");
+        assert_eq!(format_frequency(24.0e6), "24.0 MHz");
+    }
+}
@@ -0,0 +1,349 @@
+// 計測の設定・実行・結果
+
+use std::fmt;
+use std::hint::black_box;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::output::{write_counter_note, write_human};
+use crate::stats::Stats;
+use crate::timer::CycleCounter;
+
+/// 計測前の空回し（ウォームアップ）の量
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Warmup {
+    /// 指定回数だけ呼び出す
+    Iterations(usize),
+    /// 指定時間が経過するまで呼び出す
+    Duration(Duration),
+}
+
+impl fmt::Display for Warmup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warmup::Iterations(n) => write!(f, "{}回", n),
+            Warmup::Duration(d) => write!(f, "{}秒", d.as_secs_f64()),
+        }
+    }
+}
+
+/// 計測の設定
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchConfig {
+    /// 計測前の空回し（ウォームアップ）
+    pub warmup: Warmup,
+    /// 計測するサンプル数（外側のループ回数）
+    pub samples: usize,
+    /// 1サンプルあたりの呼び出し回数（内側のループ回数）
+    pub iterations_per_sample: usize,
+}
+
+impl Default for BenchConfig {
+    /// 従来のベンチマークプログラムと同じ設定
+    /// （ウォームアップ1000万回、2^14 サンプル × 2^10 回 = 2^24 回の計測）
+    fn default() -> Self {
+        BenchConfig {
+            warmup: Warmup::Iterations(10_000_000),
+            samples: 16384,
+            iterations_per_sample: 1024,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// 動作確認などのための短い設定（`--quick`）
+    pub fn quick() -> Self {
+        BenchConfig {
+            warmup: Warmup::Iterations(100_000),
+            samples: 1024,
+            iterations_per_sample: 256,
+        }
+    }
+
+    /// 計測する呼び出しの総数（ウォームアップを除く）
+    pub fn total_iterations(&self) -> u128 {
+        self.samples as u128 * self.iterations_per_sample as u128
+    }
+}
+
+/// 時間指定のウォームアップで、経過時間を確認する間隔（呼び出し回数）
+const WARMUP_CHECK_INTERVAL: usize = 64;
+
+/// 1つの処理を計測した結果
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    /// 登録時の名前（バックエンド名など）
+    pub name: String,
+    /// 1回の呼び出しで処理するバイト数（スループットの計算に使用、不明な場合は0）
+    pub bytes_per_iteration: usize,
+    /// 計測に使用した設定
+    pub config: BenchConfig,
+    /// 各サンプルの所要時間（ナノ秒、`iterations_per_sample` 回の呼び出しあたり）
+    pub samples_ns: Vec<u128>,
+    /// サンプルの統計値
+    pub stats: Stats,
+    /// 計測に使用したサイクルカウンタ（使用できない場合は `None`）
+    pub counter: Option<CycleCounter>,
+    /// 各サンプルのカウンタのサイクル数（`samples_ns` と同じサンプル、カウンタがない場合は空）
+    pub samples_cycles: Vec<u64>,
+}
+
+impl BenchResult {
+    /// 1回の呼び出しあたりの平均時間（ナノ秒）
+    pub fn per_call_ns(&self) -> f64 {
+        let total_ns: u128 = self.samples_ns.iter().sum();
+        total_ns as f64 / self.config.total_iterations() as f64
+    }
+
+    /// 平均のスループット（MiB/秒）、処理バイト数が不明な場合は `None`
+    pub fn mib_per_sec(&self) -> Option<f64> {
+        if self.bytes_per_iteration == 0 {
+            return None;
+        }
+        Some(self.bytes_per_iteration as f64 / (self.per_call_ns() / 1e9) / (1024.0 * 1024.0))
+    }
+
+    /// 1回の呼び出しあたりの平均サイクル数（カウンタのサイクル）、カウンタがない場合は `None`
+    pub fn cycles_per_call(&self) -> Option<f64> {
+        self.counter?;
+        let total_cycles: u128 = self.samples_cycles.iter().map(|&cycles| cycles as u128).sum();
+        Some(total_cycles as f64 / self.config.total_iterations() as f64)
+    }
+
+    /// 1バイトあたりの平均サイクル数、カウンタがないか処理バイト数が不明な場合は `None`
+    pub fn cycles_per_byte(&self) -> Option<f64> {
+        if self.bytes_per_iteration == 0 {
+            return None;
+        }
+        Some(self.cycles_per_call()? / self.bytes_per_iteration as f64)
+    }
+
+    /// 1ブロック（`block_size` バイト）あたりの平均サイクル数
+    pub fn cycles_per_block(&self, block_size: usize) -> Option<f64> {
+        Some(self.cycles_per_byte()? * block_size as f64)
+    }
+
+    /// 従来のベンチマークプログラムと同じ形式で結果を標準出力に表示
+    /// （ブロックサイズが分からないため、1ブロックあたりのサイクル数は表示しない）
+    pub fn print_report(&self) {
+        // 標準出力への書き込みに失敗した場合は println! と同様にパニックする
+        let mut out = io::stdout().lock();
+        write_human(&mut out, self, None)
+            .and_then(|()| write_counter_note(&mut out, self.counter.as_ref()))
+            .expect("failed printing to stdout");
+    }
+}
+
+/// `f` を `config` に従ってウォームアップしてから計測する
+/// `f` は1回の呼び出しで計測対象の処理を1回実行するクロージャで、
+/// 最適化で処理が削除されないよう、内部で入出力を `black_box` に通してください。
+pub fn run_bench<F: FnMut()>(
+    name: &str,
+    bytes_per_iteration: usize,
+    config: &BenchConfig,
+    mut f: F,
+) -> BenchResult {
+    // 空回し (ウォームアップ)
+    match config.warmup {
+        Warmup::Iterations(n) => {
+            for i in 0..n {
+                f();
+                black_box(i);
+            }
+        }
+        Warmup::Duration(duration) => {
+            let start = Instant::now();
+            while start.elapsed() < duration {
+                for i in 0..WARMUP_CHECK_INTERVAL {
+                    f();
+                    black_box(i);
+                }
+            }
+        }
+    }
+
+    // ウォームアップでコアの周波数が上がってから較正する（較正はプロセスで最初の1回のみ）
+    let counter = CycleCounter::detect();
+
+    let mut samples_ns: Vec<u128> = Vec::with_capacity(config.samples);
+    let mut samples_cycles: Vec<u64> = Vec::with_capacity(if counter.is_some() { config.samples } else { 0 });
+    for i in 0..config.samples {
+        let start = Instant::now();
+        let cycles_start = counter.map(|counter| counter.start());
+        for j in 0..config.iterations_per_sample {
+            f();
+            black_box(j); // ループ変数を black_box に入れることでループ自体の最適化を抑制
+        }
+        if let (Some(counter), Some(cycles_start)) = (counter, cycles_start) {
+            samples_cycles.push(counter.stop().wrapping_sub(cycles_start));
+        }
+        samples_ns.push(start.elapsed().as_nanos());
+        black_box(i);
+    }
+
+    let stats = Stats::from_samples(&samples_ns);
+    BenchResult {
+        name: name.to_string(),
+        bytes_per_iteration,
+        config: config.clone(),
+        samples_ns,
+        stats,
+        counter,
+        samples_cycles,
+    }
+}
+
+/// 登録された処理
+struct Bench<'a> {
+    name: String,
+    bytes_per_iteration: usize,
+    f: Box<dyn FnMut() + 'a>,
+}
+
+/// 計測する処理を登録し、同じ設定でまとめて計測するハーネス
+pub struct Harness<'a> {
+    config: BenchConfig,
+    benches: Vec<Bench<'a>>,
+}
+
+impl<'a> Harness<'a> {
+    pub fn new(config: BenchConfig) -> Self {
+        Harness { config, benches: Vec::new() }
+    }
+
+    /// 計測に使用する設定
+    pub fn config(&self) -> &BenchConfig {
+        &self.config
+    }
+
+    /// 計測する処理を登録する（`f` の条件は `run_bench` と同じ）
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        bytes_per_iteration: usize,
+        f: impl FnMut() + 'a,
+    ) -> &mut Self {
+        self.benches.push(Bench {
+            name: name.into(),
+            bytes_per_iteration,
+            f: Box::new(f),
+        });
+        self
+    }
+
+    /// 登録順にすべての処理を計測する
+    pub fn run(&mut self) -> Vec<BenchResult> {
+        let config = &self.config;
+        self.benches
+            .iter_mut()
+            .map(|bench| run_bench(&bench.name, bench.bytes_per_iteration, config, &mut bench.f))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_config() -> BenchConfig {
+        BenchConfig { warmup: Warmup::Iterations(5), samples: 4, iterations_per_sample: 3 }
+    }
+
+    // ウォームアップと計測で指定した回数だけ呼び出されること
+    #[test]
+    fn test_run_bench_call_count() {
+        let mut calls = 0usize;
+        let result = run_bench("count", 64, &small_config(), || calls += 1);
+        assert_eq!(calls, 5 + 4 * 3);
+        assert_eq!(result.name, "count");
+        assert_eq!(result.samples_ns.len(), 4);
+        assert_eq!(result.config.total_iterations(), 12);
+        assert_eq!(result.stats, Stats::from_samples(&result.samples_ns));
+    }
+
+    #[test]
+    fn test_harness_runs_in_registration_order() {
+        let (mut a, mut b) = (0usize, 0usize);
+        let results = {
+            let mut harness = Harness::new(small_config());
+            harness.register("a", 64, || a += 1).register("b", 0, || b += 1);
+            harness.run()
+        };
+        assert_eq!((a, b), (17, 17));
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(results[1].mib_per_sec().is_none());
+    }
+
+    #[test]
+    fn test_per_call_and_throughput() {
+        let result = BenchResult {
+            name: "x".to_string(),
+            bytes_per_iteration: 1024 * 1024,
+            config: BenchConfig { warmup: Warmup::Iterations(0), samples: 2, iterations_per_sample: 4 },
+            samples_ns: vec![1_000_000_000, 3_000_000_000],
+            stats: Stats::from_samples(&[1_000_000_000, 3_000_000_000]),
+            counter: None,
+            samples_cycles: Vec::new(),
+        };
+        assert_eq!(result.per_call_ns(), 500_000_000.0);
+        assert_eq!(result.mib_per_sec(), Some(2.0));
+        assert_eq!(result.cycles_per_call(), None);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let result = BenchResult {
+            name: "x".to_string(),
+            bytes_per_iteration: 256,
+            config: BenchConfig { warmup: Warmup::Iterations(0), samples: 2, iterations_per_sample: 4 },
+            samples_ns: vec![100, 300],
+            stats: Stats::from_samples(&[100, 300]),
+            counter: Some(CycleCounter::for_test(1.0e9, Some(1.0e9))),
+            samples_cycles: vec![4096, 6144],
+        };
+        assert_eq!(result.cycles_per_call(), Some(1280.0));
+        assert_eq!(result.cycles_per_byte(), Some(5.0));
+        assert_eq!(result.cycles_per_block(64), Some(320.0));
+    }
+
+    // カウンタを使用できる場合は、サンプルごとにサイクル数も記録されること
+    #[test]
+    fn test_run_bench_records_cycles() {
+        let result = run_bench("cycles", 64, &small_config(), || {
+            black_box(0);
+        });
+        assert_eq!(result.counter, CycleCounter::detect());
+        let expected = if result.counter.is_some() { 4 } else { 0 };
+        assert_eq!(result.samples_cycles.len(), expected);
+    }
+
+    #[test]
+    fn test_default_config_matches_previous_constants() {
+        let config = BenchConfig::default();
+        assert_eq!(config.warmup, Warmup::Iterations(10_000_000));
+        assert_eq!(config.total_iterations(), 1 << 24);
+    }
+
+    // 時間指定のウォームアップは指定時間以上呼び出してから計測すること
+    #[test]
+    fn test_warmup_duration() {
+        let config = BenchConfig {
+            warmup: Warmup::Duration(Duration::from_millis(20)),
+            samples: 1,
+            iterations_per_sample: 1,
+        };
+        let start = Instant::now();
+        let mut calls = 0usize;
+        run_bench("time", 0, &config, || calls += 1);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(calls > 1);
+        assert_eq!((calls - 1) % WARMUP_CHECK_INTERVAL, 0);
+    }
+
+    #[test]
+    fn test_warmup_display() {
+        assert_eq!(Warmup::Iterations(100).to_string(), "100回");
+        assert_eq!(Warmup::Duration(Duration::from_millis(1500)).to_string(), "1.5秒");
+    }
+}
@@ -0,0 +1,146 @@
+// 計測したサンプル（ナノ秒）の統計値
+
+/// サンプルの統計値（単位はすべてナノ秒、1サンプルあたり）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    /// 平均値
+    pub mean_ns: f64,
+    /// 中央値（サンプル数が偶数の場合は中央の2つの平均）
+    pub median_ns: f64,
+    /// 標準偏差（母標準偏差）
+    pub std_dev_ns: f64,
+    /// 最小値
+    pub min_ns: f64,
+    /// 5パーセンタイル
+    pub p5_ns: f64,
+    /// 25パーセンタイル
+    pub p25_ns: f64,
+    /// 75パーセンタイル
+    pub p75_ns: f64,
+    /// 95パーセンタイル
+    pub p95_ns: f64,
+    /// 99パーセンタイル
+    pub p99_ns: f64,
+    /// 最大値
+    pub max_ns: f64,
+}
+
+impl Stats {
+    /// サンプルから統計値を計算する（空の場合はすべて0）
+    pub fn from_samples(samples: &[u128]) -> Self {
+        if samples.is_empty() {
+            return Stats::ZERO;
+        }
+        let n = samples.len() as f64;
+
+        // 平均値
+        let sum: u128 = samples.iter().sum();
+        let mean = sum as f64 / n;
+
+        // 中央値
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let median = if sorted.len().is_multiple_of(2) {
+            let mid = sorted.len() / 2;
+            (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+        } else {
+            sorted[sorted.len() / 2] as f64
+        };
+
+        // 標準偏差
+        let variance: f64 = samples
+            .iter()
+            .map(|&x| {
+                let diff = x as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / n;
+
+        Stats {
+            mean_ns: mean,
+            median_ns: median,
+            std_dev_ns: variance.sqrt(),
+            min_ns: sorted[0] as f64,
+            p5_ns: percentile(&sorted, 5),
+            p25_ns: percentile(&sorted, 25),
+            p75_ns: percentile(&sorted, 75),
+            p95_ns: percentile(&sorted, 95),
+            p99_ns: percentile(&sorted, 99),
+            max_ns: sorted[sorted.len() - 1] as f64,
+        }
+    }
+
+    /// サンプルが空の場合の統計値
+    const ZERO: Stats = Stats {
+        mean_ns: 0.0,
+        median_ns: 0.0,
+        std_dev_ns: 0.0,
+        min_ns: 0.0,
+        p5_ns: 0.0,
+        p25_ns: 0.0,
+        p75_ns: 0.0,
+        p95_ns: 0.0,
+        p99_ns: 0.0,
+        max_ns: 0.0,
+    };
+}
+
+/// 昇順に並べたサンプルの `p` パーセンタイル（最近順位法: 全体の p% 以上が含まれる最小の値）
+fn percentile(sorted: &[u128], p: usize) -> f64 {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1] as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odd_number_of_samples() {
+        let stats = Stats::from_samples(&[30, 10, 20]);
+        assert_eq!(stats.mean_ns, 20.0);
+        assert_eq!(stats.median_ns, 20.0);
+        assert!((stats.std_dev_ns - (200.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    }
+
+    // サンプル数が偶数の場合、中央値は中央の2つの平均になること
+    #[test]
+    fn test_even_number_of_samples() {
+        let stats = Stats::from_samples(&[4, 1, 3, 2]);
+        assert_eq!(stats.mean_ns, 2.5);
+        assert_eq!(stats.median_ns, 2.5);
+    }
+
+    #[test]
+    fn test_constant_samples_have_zero_std_dev() {
+        let stats = Stats::from_samples(&[7; 5]);
+        assert_eq!(stats.std_dev_ns, 0.0);
+        assert_eq!((stats.min_ns, stats.p5_ns, stats.p99_ns, stats.max_ns), (7.0, 7.0, 7.0, 7.0));
+    }
+
+    #[test]
+    fn test_empty_samples() {
+        assert_eq!(Stats::from_samples(&[]), Stats::ZERO);
+    }
+
+    // 1〜100 の100サンプルでは p パーセンタイルが p になること
+    #[test]
+    fn test_percentiles() {
+        let samples: Vec<u128> = (1..=100).rev().collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min_ns, 1.0);
+        assert_eq!(stats.p5_ns, 5.0);
+        assert_eq!(stats.p25_ns, 25.0);
+        assert_eq!(stats.p75_ns, 75.0);
+        assert_eq!(stats.p95_ns, 95.0);
+        assert_eq!(stats.p99_ns, 99.0);
+        assert_eq!(stats.max_ns, 100.0);
+    }
+
+    #[test]
+    fn test_percentiles_of_few_samples() {
+        let stats = Stats::from_samples(&[10, 20, 30]);
+        assert_eq!((stats.p5_ns, stats.p25_ns, stats.p75_ns, stats.p99_ns), (10.0, 10.0, 30.0, 30.0));
+    }
+}
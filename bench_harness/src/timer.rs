@@ -0,0 +1,306 @@
+// サイクル数を計測するためのカウンタ
+//
+// x86-64 ではタイムスタンプカウンタ（rdtsc / rdtscp）、AArch64 では仮想カウンタ（cntvct_el0）を読み出します。
+// どちらもコアの動作周波数とは独立した一定の周波数で進むため、起動時に壁時計（`Instant`）と比べて
+// カウンタの周波数を較正し、あわせて依存する加算命令の連鎖からコアの動作周波数を推定します。
+// 2つの周波数が異なる場合（ターボ・省電力による変動や、AArch64 の低い周波数のカウンタ）、
+// カウンタのサイクル数はコアのサイクル数と一致しないため、出力にその旨を明記します。
+// その他のアーキテクチャではカウンタを使用せず、サイクル数は出力されません。
+
+use std::sync::OnceLock;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+use std::time::{Duration, Instant};
+
+/// カウンタの周波数とコアの動作周波数が「異なる」とみなす相対誤差
+const FREQUENCY_TOLERANCE: f64 = 0.05;
+
+/// 壁時計で較正したサイクルカウンタ
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CycleCounter {
+    name: &'static str,
+    frequency_hz: f64,
+    core_frequency_hz: Option<f64>,
+    /// 終了時に rdtscp を使用するかどうか（x86-64 のみ）
+    rdtscp: bool,
+}
+
+impl CycleCounter {
+    /// 実行中のCPUで使用できるカウンタを較正して返す（初回のみ較正し、以降は同じ値を返す）
+    pub fn detect() -> Option<CycleCounter> {
+        static COUNTER: OnceLock<Option<CycleCounter>> = OnceLock::new();
+        *COUNTER.get_or_init(calibrate)
+    }
+
+    /// カウンタの名前（"rdtscp" / "rdtsc" / "cntvct_el0"）
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// 壁時計で較正したカウンタの周波数（Hz）
+    pub fn frequency_hz(&self) -> f64 {
+        self.frequency_hz
+    }
+
+    /// 推定したコアの動作周波数（Hz）、推定できなかった場合は `None`
+    pub fn core_frequency_hz(&self) -> Option<f64> {
+        self.core_frequency_hz
+    }
+
+    /// カウンタの周波数がコアの動作周波数と異なる（または確認できない）かどうか
+    /// `true` の場合、カウンタのサイクル数はコアのサイクル数と一致しません。
+    pub fn differs_from_core(&self) -> bool {
+        match self.core_frequency_hz {
+            Some(core) => (core / self.frequency_hz - 1.0).abs() > FREQUENCY_TOLERANCE,
+            None => true,
+        }
+    }
+
+    /// 計測の開始時にカウンタを読み出す（先行する命令の完了を待ってから読み出す）
+    #[inline(always)]
+    pub fn start(&self) -> u64 {
+        arch::read_start()
+    }
+
+    /// 計測の終了時にカウンタを読み出す（計測対象の命令がすべて完了してから読み出す）
+    #[inline(always)]
+    pub fn stop(&self) -> u64 {
+        arch::read_stop(self.rdtscp)
+    }
+}
+
+/// カウンタの周波数を較正する時間
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const CALIBRATION_TIME: Duration = Duration::from_millis(50);
+
+/// コアの動作周波数の推定で、1回に実行する加算命令のループ回数（1回あたり16命令）
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const CORE_ESTIMATE_ITERATIONS: u64 = 1 << 20;
+
+/// コアの動作周波数の推定を繰り返す回数（最も速かった結果を使用）
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const CORE_ESTIMATE_TRIALS: usize = 8;
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+fn calibrate() -> Option<CycleCounter> {
+    let rdtscp = arch::has_rdtscp();
+
+    // カウンタの周波数: 一定時間の間に進んだカウント数を壁時計の経過時間で割る
+    let start = Instant::now();
+    let ticks_start = arch::read_start();
+    while start.elapsed() < CALIBRATION_TIME {
+        std::hint::spin_loop();
+    }
+    let ticks_stop = arch::read_stop(rdtscp);
+    let elapsed = start.elapsed().as_secs_f64();
+    let frequency_hz = ticks_stop.wrapping_sub(ticks_start) as f64 / elapsed;
+    if !(frequency_hz.is_finite() && frequency_hz > 0.0) {
+        return None;
+    }
+
+    Some(CycleCounter {
+        name: arch::name(rdtscp),
+        frequency_hz,
+        core_frequency_hz: estimate_core_frequency(),
+        rdtscp,
+    })
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn calibrate() -> Option<CycleCounter> {
+    None
+}
+
+/// レイテンシ1サイクルの加算命令を依存関係のある連鎖で実行し、その所要時間からコアの動作周波数を推定する
+/// 周波数の上昇を待つため複数回実行し、最も速かった結果を使用します。
+/// 即値の加算はリネーム時にまとめて処理するCPUがあるため（1サイクルに複数進み周波数を過大に推定する）、
+/// レジスタ同士の加算を使用します。
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+fn estimate_core_frequency() -> Option<f64> {
+    let cycles = (CORE_ESTIMATE_ITERATIONS * arch::ADD_CHAIN_LENGTH) as f64;
+    let fastest = (0..CORE_ESTIMATE_TRIALS)
+        .map(|_| {
+            let start = Instant::now();
+            arch::add_chain(CORE_ESTIMATE_ITERATIONS);
+            start.elapsed()
+        })
+        .min()?;
+    let frequency_hz = cycles / fastest.as_secs_f64();
+    frequency_hz.is_finite().then_some(frequency_hz)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod arch {
+    use std::arch::asm;
+    use std::arch::x86_64::{__cpuid, __rdtscp, _mm_lfence, _rdtsc};
+
+    /// `add_chain` の1ループあたりの加算命令の数
+    pub const ADD_CHAIN_LENGTH: u64 = 16;
+
+    pub fn name(rdtscp: bool) -> &'static str {
+        if rdtscp {
+            "rdtscp"
+        } else {
+            "rdtsc"
+        }
+    }
+
+    /// rdtscp 命令に対応しているかどうか（CPUID 0x80000001 の EDX ビット27）
+    pub fn has_rdtscp() -> bool {
+        let max_extended = __cpuid(0x8000_0000).eax;
+        max_extended >= 0x8000_0001 && __cpuid(0x8000_0001).edx & (1 << 27) != 0
+    }
+
+    #[inline(always)]
+    pub fn read_start() -> u64 {
+        // SAFETY: lfence（SSE2）と rdtsc は x86-64 では常に使用できる
+        unsafe {
+            _mm_lfence();
+            let ticks = _rdtsc();
+            _mm_lfence();
+            ticks
+        }
+    }
+
+    #[inline(always)]
+    pub fn read_stop(rdtscp: bool) -> u64 {
+        // SAFETY: rdtscp は has_rdtscp() で対応を確認した場合のみ使用する
+        unsafe {
+            let ticks = if rdtscp {
+                let mut aux = 0u32;
+                __rdtscp(&mut aux)
+            } else {
+                _mm_lfence();
+                _rdtsc()
+            };
+            _mm_lfence();
+            ticks
+        }
+    }
+
+    /// `iterations` × 16 回の依存する加算を実行する
+    pub fn add_chain(iterations: u64) {
+        let mut value = 0u64;
+        let mut remaining = iterations;
+        // SAFETY: レジスタのみを操作し、メモリやスタックには触れない
+        unsafe {
+            asm!(
+                "2:",
+                ".rept 16",
+                "add {value}, {step}",
+                ".endr",
+                "sub {remaining}, 1",
+                "jnz 2b",
+                value = inout(reg) value,
+                remaining = inout(reg) remaining,
+                step = in(reg) 1u64,
+                options(nomem, nostack),
+            );
+        }
+        std::hint::black_box((value, remaining));
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arch {
+    use std::arch::asm;
+
+    /// `add_chain` の1ループあたりの加算命令の数
+    pub const ADD_CHAIN_LENGTH: u64 = 16;
+
+    pub fn name(_rdtscp: bool) -> &'static str {
+        "cntvct_el0"
+    }
+
+    pub fn has_rdtscp() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    pub fn read_start() -> u64 {
+        let ticks: u64;
+        // SAFETY: cntvct_el0 はユーザー空間から読み出せる（isb で先行する命令の完了を待つ）
+        unsafe {
+            asm!("isb", "mrs {}, cntvct_el0", out(reg) ticks, options(nomem, nostack));
+        }
+        ticks
+    }
+
+    #[inline(always)]
+    pub fn read_stop(_rdtscp: bool) -> u64 {
+        read_start()
+    }
+
+    /// `iterations` × 16 回の依存する加算を実行する
+    pub fn add_chain(iterations: u64) {
+        let mut value = 0u64;
+        let mut remaining = iterations;
+        // SAFETY: レジスタのみを操作し、メモリやスタックには触れない
+        unsafe {
+            asm!(
+                "2:",
+                ".rept 16",
+                "add {value}, {value}, {step}",
+                ".endr",
+                "subs {remaining}, {remaining}, #1",
+                "b.ne 2b",
+                value = inout(reg) value,
+                remaining = inout(reg) remaining,
+                step = in(reg) 1u64,
+                options(nomem, nostack),
+            );
+        }
+        std::hint::black_box((value, remaining));
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod arch {
+    // カウンタに対応していないアーキテクチャでは `CycleCounter` が作成されないため呼び出されない
+
+    pub fn read_start() -> u64 {
+        unreachable!("このアーキテクチャではサイクルカウンタを使用できません")
+    }
+
+    pub fn read_stop(_rdtscp: bool) -> u64 {
+        unreachable!("このアーキテクチャではサイクルカウンタを使用できません")
+    }
+}
+
+#[cfg(test)]
+impl CycleCounter {
+    /// 他のモジュールのテストで使用する、指定した周波数のカウンタ
+    pub(crate) fn for_test(frequency_hz: f64, core_frequency_hz: Option<f64>) -> CycleCounter {
+        CycleCounter { name: "test", frequency_hz, core_frequency_hz, rdtscp: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(frequency_hz: f64, core_frequency_hz: Option<f64>) -> CycleCounter {
+        CycleCounter::for_test(frequency_hz, core_frequency_hz)
+    }
+
+    #[test]
+    fn test_differs_from_core() {
+        assert!(!counter(3.0e9, Some(3.1e9)).differs_from_core());
+        assert!(counter(3.0e9, Some(4.2e9)).differs_from_core());
+        assert!(counter(24.0e6, Some(2.4e9)).differs_from_core());
+        assert!(counter(3.0e9, None).differs_from_core());
+    }
+
+    // 対応するアーキテクチャではカウンタが較正でき、読み出した値が単調に増加すること
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn test_detect() {
+        let counter = CycleCounter::detect().expect("サイクルカウンタを較正できません");
+        assert!(counter.frequency_hz() > 1.0e6);
+        assert_eq!(CycleCounter::detect(), Some(counter));
+
+        let start = counter.start();
+        std::thread::sleep(Duration::from_millis(1));
+        let stop = counter.stop();
+        assert!(stop > start);
+    }
+}
@@ -0,0 +1,37 @@
+// コマンドごとに異なるハッシュアルゴリズムの抽象化
+
+use std::io::{self, Read};
+
+/// 各コマンドが扱うハッシュアルゴリズム（出力と --check での自動判別に使用）
+pub trait ChecksumAlgorithm: Copy + Eq + std::fmt::Debug + 'static {
+    /// このコマンドが出力に使用するアルゴリズム
+    const DEFAULT: Self;
+
+    /// 検証モードで自動判別するアルゴリズムの一覧
+    const ALL: &'static [Self];
+
+    /// BSD形式（--tag）で使用するアルゴリズム名
+    fn tag(self) -> &'static str;
+
+    /// ハッシュ値の16進表記の長さ
+    fn hex_len(self) -> usize;
+
+    /// GNU形式の行で、16進表記の長さからアルゴリズムを判別する
+    fn from_hex_len(len: usize) -> Option<Self>;
+
+    /// リーダーから終端まで読み込み、ハッシュ値を計算する
+    fn digest<R: Read>(self, reader: R) -> io::Result<Vec<u8>>;
+}
+
+/// リーダーから終端まで読み込み、読み込んだ順にデータを渡す（io::Write を実装しない計算器用）
+pub fn read_chunks<R: Read>(mut reader: R, mut update: impl FnMut(&[u8])) -> io::Result<()> {
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => update(&buf[..n]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
@@ -0,0 +1,463 @@
+// チェックサム一覧の検証モード（--check）
+//
+// GNU形式（`<hex>  <name>` / `<hex> *<name>`）とBSD形式（`SHA256 (<name>) = <hex>`）の行を読み、
+// 記載されたファイルのハッシュ値を再計算して OK / FAILED を報告します。
+// アルゴリズムはBSD形式ではタグから、GNU形式では16進表記の長さから自動判別するため、
+// 例えば sha256sum ではSHA-224とSHA-256が混在した一覧も1回の実行で検証できます。
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+use crate::quote::quotef;
+use crate::sys::{open_bytes, os_bytes, strerror};
+use crate::{ChecksumAlgorithm, Options};
+
+/// チェックサム一覧の1行を解析した結果
+#[derive(Debug, PartialEq, Eq)]
+struct Entry<A> {
+    algorithm: A,
+    hex: Vec<u8>,    // 期待するハッシュ値の16進表記（大文字・小文字は区別しない）
+    name: Vec<u8>,   // エスケープを解除したファイル名
+}
+
+/// 一覧全体で共通の行形式（GNUの bsd_reversed 相当）
+///
+/// `<hex> <name>` のようにモード文字のない形式と、通常の `<hex>  <name>` 形式の混在は、
+/// 先頭に空白を含むファイル名を悪用した取り違えを防ぐため受け付けません。
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    Unknown,
+    Standard,
+    Reversed,
+}
+
+/// 1つのチェックサム一覧の検証結果の集計
+#[derive(Default)]
+struct Counts {
+    misformatted: usize,    // 解析できなかった行
+    unreadable: usize,      // 開けなかった・読めなかったファイル
+    mismatched: usize,      // ハッシュ値が一致しなかったファイル
+    matched: usize,         // ハッシュ値が一致したファイル
+    properly_formatted: bool,
+}
+
+/// 指定されたすべてのチェックサム一覧を検証し、すべて成功したかどうかを返す
+pub fn check_files<A: ChecksumAlgorithm>(prog: &str, options: &Options) -> bool {
+    let mut ok = true;
+    for file in &options.files {
+        ok &= check_manifest::<A>(prog, file, options);
+    }
+    ok
+}
+
+fn check_manifest<A: ChecksumAlgorithm>(prog: &str, file: &OsStr, options: &Options) -> bool {
+    let is_stdin = file == "-";
+    let display = if is_stdin {
+        quotef(b"standard input")
+    } else {
+        quotef(&os_bytes(file))
+    };
+
+    let mut reader: Box<dyn BufRead> = if is_stdin {
+        Box::new(io::stdin().lock())
+    } else {
+        match File::open(file) {
+            Ok(f) => Box::new(BufReader::new(f)),
+            Err(err) => {
+                eprintln!("{}: {}: {}", prog, display, strerror(&err));
+                return false;
+            }
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut counts = Counts::default();
+    let mut layout = Layout::Unknown;
+    let mut line = Vec::new();
+    let mut line_number = 0usize;
+
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(_) => {
+                let _ = out.flush();
+                eprintln!("{}: {}: read error", prog, display);
+                return false;
+            }
+        }
+        line_number += 1;
+
+        // 行末の改行と、Windows形式の復帰文字を取り除く
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        // 空行とコメント行は無視する
+        if line.is_empty() || line[0] == b'#' {
+            continue;
+        }
+
+        let entry = match parse_line::<A>(&line, &mut layout) {
+            Some(entry) if !(is_stdin && entry.name == b"-") => entry,
+            _ => {
+                counts.misformatted += 1;
+                if options.warn {
+                    let _ = out.flush();
+                    eprintln!(
+                        "{}: {}: {}: improperly formatted {} checksum line",
+                        prog,
+                        display,
+                        line_number,
+                        A::DEFAULT.tag()
+                    );
+                }
+                continue;
+            }
+        };
+        counts.properly_formatted = true;
+
+        let result = if entry.name == b"-" {
+            entry.algorithm.digest(io::stdin().lock()).map(Some)
+        } else {
+            match open_bytes(&entry.name) {
+                Ok(f) => entry.algorithm.digest(f).map(Some),
+                Err(err) if options.ignore_missing && err.kind() == io::ErrorKind::NotFound => {
+                    Ok(None)
+                }
+                Err(err) => Err(err),
+            }
+        };
+
+        let status = match result {
+            // --ignore-missing 指定時、存在しないファイルは何も報告しない
+            Ok(None) => continue,
+            Ok(Some(digest)) => {
+                let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+                if hex.as_bytes().eq_ignore_ascii_case(&entry.hex) {
+                    counts.matched += 1;
+                    if options.quiet {
+                        None
+                    } else {
+                        Some("OK")
+                    }
+                } else {
+                    counts.mismatched += 1;
+                    Some("FAILED")
+                }
+            }
+            Err(err) => {
+                let _ = out.flush();
+                eprintln!("{}: {}: {}", prog, quotef(&entry.name), strerror(&err));
+                counts.unreadable += 1;
+                Some("FAILED open or read")
+            }
+        };
+
+        if let Some(status) = status {
+            if !options.status {
+                let mut report = format_name(&entry.name);
+                report.extend_from_slice(b": ");
+                report.extend_from_slice(status.as_bytes());
+                report.push(b'\n');
+                if out.write_all(&report).is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+    let _ = out.flush();
+
+    if !counts.properly_formatted {
+        eprintln!("{}: {}: no properly formatted checksum lines found", prog, display);
+    } else if !options.status {
+        if counts.misformatted != 0 {
+            eprintln!(
+                "{}: WARNING: {} {} improperly formatted",
+                prog,
+                counts.misformatted,
+                plural(counts.misformatted, "line is", "lines are")
+            );
+        }
+        if counts.unreadable != 0 {
+            eprintln!(
+                "{}: WARNING: {} listed {} could not be read",
+                prog,
+                counts.unreadable,
+                plural(counts.unreadable, "file", "files")
+            );
+        }
+        if counts.mismatched != 0 {
+            eprintln!(
+                "{}: WARNING: {} computed {} did NOT match",
+                prog,
+                counts.mismatched,
+                plural(counts.mismatched, "checksum", "checksums")
+            );
+        }
+        if options.ignore_missing && counts.matched == 0 {
+            eprintln!("{}: {}: no file was verified", prog, display);
+        }
+    }
+
+    counts.properly_formatted
+        && counts.mismatched == 0
+        && counts.unreadable == 0
+        && (!options.strict || counts.misformatted == 0)
+        && (!options.ignore_missing || counts.matched > 0)
+}
+
+fn plural<'a>(n: usize, one: &'a str, many: &'a str) -> &'a str {
+    if n == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+/// 検証結果の行に出力するファイル名
+/// 改行を含む場合のみ、行頭に `\` を付けて `\` と改行をエスケープする（GNU coreutils 9.1 と同じ）。
+fn format_name(name: &[u8]) -> Vec<u8> {
+    if !name.contains(&b'\n') {
+        return name.to_vec();
+    }
+    let mut out = vec![b'\\'];
+    for &b in name {
+        match b {
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+fn is_white(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+/// チェックサム一覧の1行を解析する（GNU coreutils の split_3 相当）
+fn parse_line<A: ChecksumAlgorithm>(line: &[u8], layout: &mut Layout) -> Option<Entry<A>> {
+    let mut i = line.iter().position(|&b| !is_white(b))?;
+    let escaped = line[i] == b'\\';
+    if escaped {
+        i += 1;
+    }
+
+    // BSD形式: タグの長いものから照合する（"SHA512t256" と "SHA512" のような前方一致を区別するため）
+    let mut tagged: Vec<A> = A::ALL.to_vec();
+    tagged.sort_by_key(|algorithm| std::cmp::Reverse(algorithm.tag().len()));
+    for algorithm in tagged {
+        let tag = algorithm.tag().as_bytes();
+        if line[i..].starts_with(tag) {
+            let mut j = i + tag.len();
+            if line.get(j) == Some(&b' ') {
+                j += 1;
+            }
+            if line.get(j) != Some(&b'(') {
+                return None;
+            }
+            return parse_bsd(&line[j + 1..], algorithm, escaped);
+        }
+    }
+
+    // GNU形式: 先頭の16進表記の長さからアルゴリズムを判別する
+    let rest = &line[i..];
+    let hex_len = rest.iter().position(|&b| is_white(b))?;
+    let algorithm = A::from_hex_len(hex_len)?;
+    if rest.len() < hex_len + 2 || !rest[..hex_len].iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+
+    let mut j = hex_len + 1;
+    if rest.len() - j == 1 || (rest[j] != b' ' && rest[j] != b'*') {
+        if *layout == Layout::Standard {
+            return None;
+        }
+        *layout = Layout::Reversed;
+    } else if *layout != Layout::Reversed {
+        *layout = Layout::Standard;
+        j += 1;   // モード文字（' ' または '*'）を読み飛ばす
+    }
+
+    let name = if escaped {
+        unescape(&rest[j..])?
+    } else {
+        rest[j..].to_vec()
+    };
+    Some(Entry {
+        algorithm,
+        hex: rest[..hex_len].to_vec(),
+        name,
+    })
+}
+
+/// BSD形式の `(` 以降（`<name>) = <hex>`）を解析する
+fn parse_bsd<A: ChecksumAlgorithm>(s: &[u8], algorithm: A, escaped: bool) -> Option<Entry<A>> {
+    // ファイル名は最後の ')' までとする（名前自体に ')' を含む場合に対応）
+    let close = s.iter().rposition(|&b| b == b')')?;
+    let name = if escaped {
+        unescape(&s[..close])?
+    } else {
+        s[..close].to_vec()
+    };
+
+    let mut i = close + 1;
+    while i < s.len() && is_white(s[i]) {
+        i += 1;
+    }
+    if s.get(i) != Some(&b'=') {
+        return None;
+    }
+    i += 1;
+    while i < s.len() && is_white(s[i]) {
+        i += 1;
+    }
+
+    let hex = &s[i..];
+    if hex.len() != algorithm.hex_len() || !hex.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    Some(Entry {
+        algorithm,
+        hex: hex.to_vec(),
+        name,
+    })
+}
+
+/// `\\`・`\n`・`\r` のエスケープを解除する（それ以外のエスケープやNULは不正とする）
+fn unescape(s: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut iter = s.iter();
+    while let Some(&b) = iter.next() {
+        match b {
+            b'\\' => match iter.next()? {
+                b'\\' => out.push(b'\\'),
+                b'n' => out.push(b'\n'),
+                b'r' => out.push(b'\r'),
+                _ => return None,
+            },
+            0 => return None,
+            _ => out.push(b),
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_512: &str = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+    const ABC_384: &str = "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7";
+
+    /// sha512sum と同じタグ・長さを持つテスト用のアルゴリズム（解析のみに使用）
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Algorithm {
+        Sha384,
+        Sha512,
+        Sha512_256,
+    }
+
+    impl ChecksumAlgorithm for Algorithm {
+        const DEFAULT: Algorithm = Algorithm::Sha512;
+        const ALL: &'static [Algorithm] =
+            &[Algorithm::Sha384, Algorithm::Sha512, Algorithm::Sha512_256];
+
+        fn tag(self) -> &'static str {
+            match self {
+                Algorithm::Sha384 => "SHA384",
+                Algorithm::Sha512 => "SHA512",
+                Algorithm::Sha512_256 => "SHA512t256",
+            }
+        }
+
+        fn hex_len(self) -> usize {
+            match self {
+                Algorithm::Sha384 => 96,
+                Algorithm::Sha512 => 128,
+                Algorithm::Sha512_256 => 64,
+            }
+        }
+
+        fn from_hex_len(len: usize) -> Option<Self> {
+            [Algorithm::Sha384, Algorithm::Sha512]
+                .into_iter()
+                .find(|algorithm| algorithm.hex_len() == len)
+        }
+
+        fn digest<R: std::io::Read>(self, _reader: R) -> io::Result<Vec<u8>> {
+            unreachable!()
+        }
+    }
+
+    fn parse(line: &str) -> Option<Entry<Algorithm>> {
+        parse_line(line.as_bytes(), &mut Layout::Unknown)
+    }
+
+    // GNU形式のテキスト・バイナリ行と、16進表記の長さによるアルゴリズム判別
+    #[test]
+    fn test_parse_gnu_lines() {
+        let entry = parse(&format!("{}  file name", ABC_512)).unwrap();
+        assert_eq!(entry.algorithm, Algorithm::Sha512);
+        assert_eq!(entry.name, b"file name");
+
+        let entry = parse(&format!("{} *bin", ABC_384)).unwrap();
+        assert_eq!(entry.algorithm, Algorithm::Sha384);
+        assert_eq!(entry.name, b"bin");
+
+        // 長さの合わない16進表記や16進数以外の文字は不正な行
+        assert_eq!(parse(&format!("{}0  x", ABC_512)), None);
+        assert_eq!(parse(&format!("{}  x", ABC_512.replace('b', "g"))), None);
+    }
+
+    // BSD形式（--tag）の行と、タグによるアルゴリズム判別
+    #[test]
+    fn test_parse_bsd_lines() {
+        let entry = parse(&format!("SHA384 (a (1).txt) = {}", ABC_384)).unwrap();
+        assert_eq!(entry.algorithm, Algorithm::Sha384);
+        assert_eq!(entry.name, b"a (1).txt");
+
+        // "SHA512" の前方一致ではなく SHA-512/256 のタグとして判別する
+        let entry = parse("SHA512t256 (abc) = 53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23").unwrap();
+        assert_eq!(entry.algorithm, Algorithm::Sha512_256);
+        assert_eq!(entry.name, b"abc");
+
+        // タグと16進表記の長さが一致しない場合は不正な行
+        assert_eq!(parse(&format!("SHA512 (a) = {}", ABC_384)), None);
+        assert_eq!(parse(&format!("SHA512 (a) {}", ABC_512)), None);
+    }
+
+    // エスケープされたファイル名の復元
+    #[test]
+    fn test_parse_escaped_names() {
+        let entry = parse(&format!("\\{}  a\\\\b\\nc", ABC_512)).unwrap();
+        assert_eq!(entry.name, b"a\\b\nc");
+
+        let entry = parse(&format!("\\SHA512 (x\\ry) = {}", ABC_512)).unwrap();
+        assert_eq!(entry.name, b"x\ry");
+
+        assert_eq!(parse(&format!("\\{}  bad\\t", ABC_512)), None);
+    }
+
+    // モード文字のない形式と通常形式の混在は受け付けない
+    #[test]
+    fn test_parse_layout_is_not_mixed() {
+        let mut layout = Layout::Unknown;
+        let reversed = format!("{} name", ABC_512);
+        let standard = format!("{}  name", ABC_512);
+
+        assert_eq!(parse_line::<Algorithm>(reversed.as_bytes(), &mut layout).unwrap().name, b"name");
+        assert!(layout == Layout::Reversed);
+        assert_eq!(parse_line::<Algorithm>(standard.as_bytes(), &mut layout).unwrap().name, b" name");
+
+        let mut layout = Layout::Unknown;
+        assert!(parse_line::<Algorithm>(standard.as_bytes(), &mut layout).is_some());
+        assert_eq!(parse_line::<Algorithm>(reversed.as_bytes(), &mut layout), None);
+    }
+}
@@ -0,0 +1,235 @@
+// コマンドライン引数の解析と、コマンド全体の実行手順
+
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+use crate::{check_files, digest_files, ChecksumAlgorithm};
+
+/// 長いオプションの一覧（GNU getopt と同様に前方一致での省略指定を受け付ける）
+const LONG_OPTIONS: &[&str] = &[
+    "binary",
+    "check",
+    "ignore-missing",
+    "quiet",
+    "status",
+    "strict",
+    "tag",
+    "text",
+    "warn",
+    "zero",
+    "help",
+    "version",
+];
+
+/// コマンドラインで指定された動作
+pub struct Options {
+    pub binary: Option<bool>,   // -b / -t の指定（後に指定したものが有効、未指定はNone）
+    pub check: bool,            // チェックサム一覧を読み込んで検証する
+    pub ignore_missing: bool,   // 検証時、存在しないファイルを失敗として扱わない
+    pub quiet: bool,            // 検証時、成功したファイルの OK を出力しない
+    pub status: bool,           // 検証時、何も出力せず終了コードのみで結果を示す
+    pub strict: bool,           // 検証時、不正な形式の行があれば失敗とする
+    pub warn: bool,             // 検証時、不正な形式の行を警告する
+    pub tag: bool,              // BSD形式で出力する
+    pub zero: bool,             // 行末をNULにし、ファイル名のエスケープを行わない
+    pub files: Vec<OsString>,   // 入力ファイル（"-" は標準入力）
+}
+
+/// 引数解析の結果
+pub enum Command {
+    Run(Options),
+    Help,
+    Version,
+}
+
+/// 引数を解析し、ハッシュ値の出力または --check による検証を行う
+///
+/// `name` は引数からプログラム名を取得できない場合に使う名前、`version` は --version で
+/// 表示する行、`print_help` は --help でプログラム名を受け取って使い方を表示する関数です。
+pub fn run<A: ChecksumAlgorithm>(name: &str, version: &str, print_help: fn(&str)) -> ExitCode {
+    let mut args = std::env::args_os();
+    let prog = args
+        .next()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.to_string());
+
+    let options = match parse_args(args) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            print_help(&prog);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("{}", version);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{}: {}", prog, message);
+            eprintln!("Try '{} --help' for more information.", prog);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let ok = if options.check {
+        check_files::<A>(&prog, &options)
+    } else {
+        digest_files::<A>(&prog, &options)
+    };
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// コマンドライン引数を解析する
+/// オプションとファイル名は任意の順序で混在でき、"--" 以降はすべてファイル名として扱います。
+pub fn parse_args(args: impl Iterator<Item = OsString>) -> Result<Command, String> {
+    let mut options = Options {
+        binary: None,
+        check: false,
+        ignore_missing: false,
+        quiet: false,
+        status: false,
+        strict: false,
+        warn: false,
+        tag: false,
+        zero: false,
+        files: Vec::new(),
+    };
+    let mut only_files = false;
+
+    for arg in args {
+        let text = arg.to_string_lossy();
+        if only_files || text == "-" || !text.starts_with('-') {
+            options.files.push(arg);
+        } else if text == "--" {
+            only_files = true;
+        } else if let Some(long) = text.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let option = match_long_option(name).map_err(|err| {
+                if value.is_some() && err.starts_with("unrecognized") {
+                    format!("unrecognized option '{}'", text)
+                } else {
+                    err
+                }
+            })?;
+            if value.is_some() {
+                return Err(format!("option '--{}' doesn't allow an argument", option));
+            }
+            match option {
+                "binary" => options.binary = Some(true),
+                "check" => options.check = true,
+                "ignore-missing" => options.ignore_missing = true,
+                "quiet" => options.set_verbosity(Verbosity::Quiet),
+                "status" => options.set_verbosity(Verbosity::Status),
+                "strict" => options.strict = true,
+                "text" => options.binary = Some(false),
+                "warn" => options.set_verbosity(Verbosity::Warn),
+                "tag" => {
+                    // GNU と同様に、--tag はバイナリモードの指定を兼ねる
+                    options.tag = true;
+                    options.binary = Some(true);
+                }
+                "zero" => options.zero = true,
+                "help" => return Ok(Command::Help),
+                "version" => return Ok(Command::Version),
+                _ => unreachable!(),
+            }
+        } else {
+            for c in text[1..].chars() {
+                match c {
+                    'b' => options.binary = Some(true),
+                    'c' => options.check = true,
+                    't' => options.binary = Some(false),
+                    'w' => options.set_verbosity(Verbosity::Warn),
+                    'z' => options.zero = true,
+                    _ => return Err(format!("invalid option -- '{}'", c)),
+                }
+            }
+        }
+    }
+
+    // GNU sha256sum / sha512sum と同じ組み合わせ検査（エラーの優先順位も合わせる）
+    if options.tag && options.binary == Some(false) {
+        return Err("--tag does not support --text mode".to_string());
+    }
+    if options.check {
+        if options.zero {
+            return Err("the --zero option is not supported when verifying checksums".to_string());
+        }
+        if options.tag {
+            return Err("the --tag option is meaningless when verifying checksums".to_string());
+        }
+        if options.binary.is_some() {
+            return Err(
+                "the --binary and --text options are meaningless when verifying checksums"
+                    .to_string(),
+            );
+        }
+    } else {
+        let check_only = [
+            (options.ignore_missing, "--ignore-missing"),
+            (options.status, "--status"),
+            (options.warn, "--warn"),
+            (options.quiet, "--quiet"),
+            (options.strict, "--strict"),
+        ];
+        if let Some((_, name)) = check_only.iter().find(|(set, _)| *set) {
+            return Err(format!(
+                "the {} option is meaningful only when verifying checksums",
+                name
+            ));
+        }
+    }
+
+    if options.files.is_empty() {
+        options.files.push(OsString::from("-"));
+    }
+    Ok(Command::Run(options))
+}
+
+/// --status・--warn・--quiet のいずれか（GNU と同様に後に指定したものだけが有効）
+enum Verbosity {
+    Status,
+    Warn,
+    Quiet,
+}
+
+impl Options {
+    fn set_verbosity(&mut self, verbosity: Verbosity) {
+        self.status = matches!(verbosity, Verbosity::Status);
+        self.warn = matches!(verbosity, Verbosity::Warn);
+        self.quiet = matches!(verbosity, Verbosity::Quiet);
+    }
+}
+
+/// 長いオプション名を完全一致、または一意な前方一致で解決する
+fn match_long_option(name: &str) -> Result<&'static str, String> {
+    if let Some(&option) = LONG_OPTIONS.iter().find(|&&option| option == name) {
+        return Ok(option);
+    }
+
+    let candidates: Vec<&'static str> = LONG_OPTIONS
+        .iter()
+        .copied()
+        .filter(|option| option.starts_with(name))
+        .collect();
+    match candidates.len() {
+        0 => Err(format!("unrecognized option '--{}'", name)),
+        1 => Ok(candidates[0]),
+        _ => {
+            let possibilities: String = candidates
+                .iter()
+                .map(|option| format!(" '--{}'", option))
+                .collect();
+            Err(format!(
+                "option '--{}' is ambiguous; possibilities:{}",
+                name, possibilities
+            ))
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// ハッシュ値の出力モード（GNU coreutils と同じテキスト・バイナリ・BSDタグ形式・NUL区切りの行）
+
+use std::fs::File;
+use std::io::{self, Write};
+
+use crate::quote::quotef;
+use crate::sys::{os_bytes, strerror};
+use crate::{ChecksumAlgorithm, Options};
+
+/// 各ファイルのハッシュ値を計算して出力し、すべて成功したかどうかを返す
+pub fn digest_files<A: ChecksumAlgorithm>(prog: &str, options: &Options) -> bool {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut ok = true;
+
+    for file in &options.files {
+        let digest = if file == "-" {
+            A::DEFAULT.digest(io::stdin().lock())
+        } else {
+            File::open(file).and_then(|f| A::DEFAULT.digest(f))
+        };
+
+        match digest {
+            Ok(digest) => {
+                let line = format_line(
+                    A::DEFAULT.tag(),
+                    &digest,
+                    &os_bytes(file),
+                    options.binary == Some(true),
+                    options.tag,
+                    options.zero,
+                );
+                if out.write_all(&line).is_err() {
+                    return false;
+                }
+            }
+            Err(err) => {
+                let _ = out.flush();
+                eprintln!("{}: {}: {}", prog, quotef(&os_bytes(file)), strerror(&err));
+                ok = false;
+            }
+        }
+    }
+
+    out.flush().is_ok() && ok
+}
+
+/// 1ファイル分の出力行を組み立てる（`algorithm` はBSD形式で使用するアルゴリズム名）
+///
+/// ファイル名に `\`・改行・復帰が含まれる場合、GNU coreutils と同様に行頭へ `\` を付け、
+/// それぞれを `\\`・`\n`・`\r` にエスケープします（`--zero` 指定時は行わない）。
+pub fn format_line(
+    algorithm: &str,
+    digest: &[u8],
+    name: &[u8],
+    binary: bool,
+    tag: bool,
+    zero: bool,
+) -> Vec<u8> {
+    let escape = !zero && name.iter().any(|&b| matches!(b, b'\\' | b'\n' | b'\r'));
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut line = Vec::with_capacity(hex.len() + name.len() + 16);
+    if escape {
+        line.push(b'\\');
+    }
+    if tag {
+        line.extend_from_slice(algorithm.as_bytes());
+        line.extend_from_slice(b" (");
+        push_name(&mut line, name, escape);
+        line.extend_from_slice(b") = ");
+        line.extend_from_slice(hex.as_bytes());
+    } else {
+        line.extend_from_slice(hex.as_bytes());
+        line.push(b' ');
+        line.push(if binary { b'*' } else { b' ' });
+        push_name(&mut line, name, escape);
+    }
+    line.push(if zero { b'\0' } else { b'\n' });
+    line
+}
+
+fn push_name(line: &mut Vec<u8>, name: &[u8], escape: bool) {
+    if !escape {
+        line.extend_from_slice(name);
+        return;
+    }
+    for &b in name {
+        match b {
+            b'\\' => line.extend_from_slice(b"\\\\"),
+            b'\n' => line.extend_from_slice(b"\\n"),
+            b'\r' => line.extend_from_slice(b"\\r"),
+            _ => line.push(b),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// sha256sum / sha512sum 互換コマンドで共通に使用する、GNU coreutils 互換の処理
+//
+// 両コマンドはこれまで、引数解析・出力行の組み立て・ファイル名のクォート・--check による
+// チェックサム一覧の検証をそれぞれ個別に実装していました。このクレートはそれらをまとめたもので、
+// 各コマンドは扱うアルゴリズムを `ChecksumAlgorithm` として実装し、`run` に渡すだけで
+// GNU coreutils と同じ形式の入出力を行えます。
+
+mod algorithm;
+mod check;
+mod cli;
+mod digest;
+mod quote;
+mod sys;
+
+pub use algorithm::{read_chunks, ChecksumAlgorithm};
+pub use check::check_files;
+pub use cli::{parse_args, run, Command, Options};
+pub use digest::{digest_files, format_line};
+pub use quote::quotef;
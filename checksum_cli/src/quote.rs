@@ -0,0 +1,84 @@
+// エラーメッセージ中のファイル名のクォート（GNU coreutils の quotef 相当）
+//
+// gnulib quotearg の shell_escape_quoting_style をCロケールの前提で再現します。
+// シェルで特別な意味を持つ文字を含む名前は '...' で囲み、制御文字や非ASCIIバイトは
+// $'\n' や $'\303' のようなエスケープ表記に置き換えます。
+
+/// ファイル名を、シェルにそのまま貼り付けられる形式の文字列に変換する
+pub fn quotef(name: &[u8]) -> String {
+    if name.is_empty() {
+        return "''".to_string();
+    }
+    if !needs_quoting(name) {
+        return String::from_utf8_lossy(name).into_owned();
+    }
+
+    // シングルクォートを含み、他の文字がすべてダブルクォート内でも安全な場合は "..." を使う
+    if name.contains(&b'\'') && name.iter().enumerate().all(|(i, &b)| double_quote_safe(b, i)) {
+        return format!("\"{}\"", String::from_utf8_lossy(name));
+    }
+
+    let mut out = String::from("'");
+    let mut in_escape = false;
+    for &b in name {
+        if is_printable(b) {
+            if in_escape {
+                // $'...' を閉じて通常のシングルクォートに戻る
+                out.push_str("''");
+                in_escape = false;
+            }
+            if b == b'\'' {
+                out.push_str("'\\''");
+            } else {
+                out.push(b as char);
+            }
+        } else {
+            if !in_escape {
+                out.push_str("'$'");
+                in_escape = true;
+            }
+            out.push_str(&c_escape(b));
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// クォートが必要な文字を含むかどうか
+fn needs_quoting(name: &[u8]) -> bool {
+    name.iter().enumerate().any(|(i, &b)| match b {
+        b'#' | b'~' => i == 0,
+        b' ' | b'!' | b'"' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b':' | b';' | b'<'
+        | b'=' | b'>' | b'?' | b'[' | b'\\' | b'^' | b'`' | b'|' => true,
+        _ => !is_printable(b),
+    })
+}
+
+/// ダブルクォートで囲んだときにシェルとC言語の両方で解釈が変わらない文字かどうか
+fn double_quote_safe(b: u8, i: usize) -> bool {
+    match b {
+        b' ' | b'\'' | b':' => true,
+        b'#' | b'~' => i == 0,
+        b'%' | b'+' | b',' | b'-' | b'.' | b'/' | b']' | b'_' | b'{' | b'}' => true,
+        _ => b.is_ascii_alphanumeric(),
+    }
+}
+
+/// Cロケールで表示可能な文字かどうか
+fn is_printable(b: u8) -> bool {
+    (0x20..0x7f).contains(&b)
+}
+
+/// 表示できないバイトをC言語形式のエスケープに変換する
+fn c_escape(b: u8) -> String {
+    match b {
+        0x07 => "\\a".to_string(),
+        0x08 => "\\b".to_string(),
+        0x0c => "\\f".to_string(),
+        b'\n' => "\\n".to_string(),
+        b'\r' => "\\r".to_string(),
+        b'\t' => "\\t".to_string(),
+        0x0b => "\\v".to_string(),
+        _ => format!("\\{:03o}", b),
+    }
+}
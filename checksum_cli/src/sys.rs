@@ -0,0 +1,38 @@
+// OSごとに扱いの異なるファイル名とエラーメッセージ
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io;
+
+/// ファイル名をバイト列として取り出す（Unixでは非UTF-8の名前もそのまま出力する）
+#[cfg(unix)]
+pub fn os_bytes(name: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    name.as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+pub fn os_bytes(name: &OsStr) -> Vec<u8> {
+    name.to_string_lossy().into_owned().into_bytes()
+}
+
+/// バイト列のファイル名でファイルを開く（チェックサム一覧に記載された名前用）
+#[cfg(unix)]
+pub fn open_bytes(name: &[u8]) -> io::Result<File> {
+    use std::os::unix::ffi::OsStrExt;
+    File::open(OsStr::from_bytes(name))
+}
+
+#[cfg(not(unix))]
+pub fn open_bytes(name: &[u8]) -> io::Result<File> {
+    File::open(String::from_utf8_lossy(name).as_ref())
+}
+
+/// "No such file or directory (os error 2)" から末尾のエラー番号を除いた、strerror相当の文字列
+pub fn strerror(err: &io::Error) -> String {
+    let message = err.to_string();
+    match message.find(" (os error ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message,
+    }
+}
@@ -0,0 +1,192 @@
+// AArch64 SHA-256 ハードウェア命令（ARMv8 Cryptography Extension）による圧縮関数
+//
+// SHA256H / SHA256H2 / SHA256SU0 / SHA256SU1 命令を使用します。
+// これらの命令はCPUによって実装されていない場合があるため、
+// `is_aarch64_feature_detected!("sha2")` で実行時に確認してから呼び出します。
+
+use core::arch::aarch64::*;
+use core::arch::asm;
+
+use crate::backend::BlockCompressor;
+use crate::{Sha256State, K};
+
+// --- AArch64 ハードウェア命令のラッパー関数群 ---
+// これらの関数は、コンパイラが自動で最適化できないCPU固有の「SHA256命令」を直接呼び出します。
+
+/// SHA256H: abcd と wk を使って efgh の状態を更新するハードウェア命令
+///
+/// # Safety
+/// 実行中のCPUが `sha2` 拡張に対応している必要があります。
+#[inline]
+#[target_feature(enable = "sha2")]
+pub unsafe fn vsha256hq_u32(
+    mut hash_efgh: uint32x4_t,
+    hash_abcd: uint32x4_t,
+    wk: uint32x4_t,
+) -> uint32x4_t {
+    asm!(
+        "SHA256H {0:q}, {1:q}, {2:v}.4S",
+        inout(vreg) hash_efgh, in(vreg) hash_abcd, in(vreg) wk,
+        options(pure, nomem, nostack, preserves_flags)
+    );
+    hash_efgh
+}
+
+/// SHA256H2: 圧縮関数の第2段階（中間変数の算出）を行うハードウェア命令
+///
+/// # Safety
+/// 実行中のCPUが `sha2` 拡張に対応している必要があります。
+#[inline]
+#[target_feature(enable = "sha2")]
+pub unsafe fn vsha256h2q_u32(
+    mut hash_efgh: uint32x4_t,
+    hash_abcd: uint32x4_t,
+    wk: uint32x4_t,
+) -> uint32x4_t {
+    asm!(
+        "SHA256H2 {0:q}, {1:q}, {2:v}.4S",
+        inout(vreg) hash_efgh, in(vreg) hash_abcd, in(vreg) wk,
+        options(pure, nomem, nostack, preserves_flags)
+    );
+    hash_efgh
+}
+
+/// SHA256SU0: メッセージスケジュールの拡張（前半）を加速
+///
+/// # Safety
+/// 実行中のCPUが `sha2` 拡張に対応している必要があります。
+#[inline]
+#[target_feature(enable = "sha2")]
+pub unsafe fn vsha256su0q_u32(mut w0_3: uint32x4_t, w4_7: uint32x4_t) -> uint32x4_t {
+    asm!(
+        "SHA256SU0 {0:v}.4S, {1:v}.4S",
+        inout(vreg) w0_3, in(vreg) w4_7,
+        options(pure, nomem, nostack, preserves_flags)
+    );
+    w0_3
+}
+
+/// SHA256SU1: メッセージスケジュールの拡張（後半）を加速
+///
+/// # Safety
+/// 実行中のCPUが `sha2` 拡張に対応している必要があります。
+#[inline]
+#[target_feature(enable = "sha2")]
+pub unsafe fn vsha256su1q_u32(
+    mut tw0_3: uint32x4_t,
+    w8_11: uint32x4_t,
+    w12_15: uint32x4_t,
+) -> uint32x4_t {
+    asm!(
+        "SHA256SU1 {0:v}.4S, {1:v}.4S, {2:v}.4S",
+        inout(vreg) tw0_3, in(vreg) w8_11, in(vreg) w12_15,
+        options(pure, nomem, nostack, preserves_flags)
+    );
+    tw0_3
+}
+
+/// 実行中のCPUがSHA-256命令に対応しているかどうか
+pub fn is_supported() -> bool {
+    std::arch::is_aarch64_feature_detected!("sha2")
+}
+
+/// 外部公開用の圧縮関数インターフェース
+/// SHA-256命令に対応していないCPUで呼び出した場合はパニックします。
+pub fn compress256(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    assert!(is_supported(), "compress256: CPU does not support the sha2 extension");
+    // SAFETY: sha2 拡張が利用できることを直前に確認している
+    unsafe { sha256_compress(state, blocks) }
+}
+
+/// AArch64 SHA命令を使用したメインの圧縮ロジック
+#[target_feature(enable = "sha2")]
+unsafe fn sha256_compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    // メモリ上の状態（abcd, efgh）をSIMDレジスタ（128bit幅）にロード
+    let mut abcd = vld1q_u32(state.as_ptr());
+    let mut efgh = vld1q_u32(state[4..].as_ptr());
+
+    for block in blocks {
+        // 各ブロック処理の最後に元の状態を加算するため、初期値を保存
+        let abcd_orig = abcd;
+        let efgh_orig = efgh;
+
+        // メッセージブロック（512bit = 64byte）をロードし、
+        // ビッグエンディアンからCPUのネイティブ形式へ変換（バイトスワップ）
+        let mut s0 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block.as_ptr())));
+        let mut s1 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[16..].as_ptr())));
+        let mut s2 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[32..].as_ptr())));
+        let mut s3 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[48..].as_ptr())));
+
+        // 4ラウンド分の計算を一括で行うマクロ
+        macro_rules! round4 {
+            ($s:expr, $t:expr) => {{
+                // メッセージスケジュール(W)と定数(K)を事前に加算
+                let tmp = vaddq_u32($s, vld1q_u32(K[$t..].as_ptr()));
+                let prev = abcd;
+                // ハードウェア命令により、ソフトウェア実装では数十行かかる処理を2命令で完了
+                abcd = vsha256hq_u32(prev, efgh, tmp);
+                efgh = vsha256h2q_u32(efgh, prev, tmp);
+            }};
+        }
+
+        // 最初の16ラウンド（入力メッセージをそのまま使用）
+        round4!(s0, 0);
+        round4!(s1, 4);
+        round4!(s2, 8);
+        round4!(s3, 12);
+
+        // 残りの48ラウンド（メッセージを拡張しながら処理）
+        for t in (16..64).step_by(16) {
+            // メッセージスケジュールの拡張をハードウェア命令で実行
+            s0 = vsha256su1q_u32(vsha256su0q_u32(s0, s1), s2, s3);
+            round4!(s0, t);
+
+            s1 = vsha256su1q_u32(vsha256su0q_u32(s1, s2), s3, s0);
+            round4!(s1, t + 4);
+
+            s2 = vsha256su1q_u32(vsha256su0q_u32(s2, s3), s0, s1);
+            round4!(s2, t + 8);
+
+            s3 = vsha256su1q_u32(vsha256su0q_u32(s3, s0), s1, s2);
+            round4!(s3, t + 12);
+        }
+
+        // ブロック処理後の状態に、処理前の状態を加算（SHA-256の仕様）
+        abcd = vaddq_u32(abcd, abcd_orig);
+        efgh = vaddq_u32(efgh, efgh_orig);
+    }
+
+    // 更新された最終的な状態をメモリ（state配列）へ書き戻す
+    vst1q_u32(state.as_mut_ptr(), abcd);
+    vst1q_u32(state[4..].as_mut_ptr(), efgh);
+}
+
+/// AArch64 SHA-256命令を使用するバックエンド
+pub struct Aarch64Sha2;
+
+impl BlockCompressor for Aarch64Sha2 {
+    fn name(&self) -> &'static str {
+        "aarch64-sha2"
+    }
+
+    fn is_available(&self) -> bool {
+        is_supported()
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        compress256(&mut state.h, blocks);
+    }
+}
+
+// qemu-user 上でも実行できるよう、対応CPUでない場合はテストを省略する
+// （例: cargo test --target aarch64-unknown-linux-gnu、ランナーは .cargo/config.toml を参照）
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_aarch64_sha2() {
+        check_backend(&Aarch64Sha2);
+    }
+}
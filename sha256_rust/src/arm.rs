@@ -0,0 +1,468 @@
+// 32ビットARM（ARMv7 / AArch32）向けのインラインアセンブリによる圧縮関数
+//
+// 次の3通りの実装を用意し、実行時に利用できる最速のものを選択します。
+// - 基本命令セット: ラウンド処理をバレルシフタ付きの演算命令で記述（すべてのARMv7で実行可能）
+// - NEON: メッセージスケジュール W[t] + K[t] を128ビットレジスタで4ワードずつ計算し、ラウンドは基本命令で処理
+// - ARMv8 Cryptography Extension（AArch32）: SHA256H / SHA256H2 / SHA256SU0 / SHA256SU1 命令を使用
+//
+// 32ビットARMでは `is_arm_feature_detected!` や `#[target_feature(enable = "neon")]` が安定版で使えないため、
+// CPUの対応状況は /proc/self/auxv の HWCAP / HWCAP2 から判定し、NEONと暗号命令はアセンブラの
+// `.fpu` / `.arch` 指定で一時的に有効にしています（ブロックの最後で armv7-unknown-linux-gnueabihf の既定である ARMv7-A / VFPv3-D16 に戻します）。
+// VFPv3-D16 のCPUでも組み込めるよう、使用するSIMDレジスタは q0〜q7（d0〜d15）に限っています。
+
+use core::arch::asm;
+use std::sync::OnceLock;
+
+use crate::backend::BlockCompressor;
+use crate::{Sha256State, K};
+
+/// HWCAP の NEON ビット（Linux の asm/hwcap.h）
+const HWCAP_NEON: u32 = 1 << 12;
+/// HWCAP2 の SHA2 ビット（Linux の asm/hwcap.h）
+const HWCAP2_SHA2: u32 = 1 << 3;
+
+/// カーネルから渡される HWCAP / HWCAP2 の値（プロセス内でキャッシュされる）
+fn hwcaps() -> (u32, u32) {
+    static HWCAPS: OnceLock<(u32, u32)> = OnceLock::new();
+    *HWCAPS.get_or_init(read_hwcaps)
+}
+
+/// 補助ベクタ（/proc/self/auxv）から HWCAP / HWCAP2 を読み取る
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn read_hwcaps() -> (u32, u32) {
+    const AT_HWCAP: u32 = 16;
+    const AT_HWCAP2: u32 = 26;
+
+    let Ok(auxv) = std::fs::read("/proc/self/auxv") else {
+        return (0, 0);
+    };
+    // 32ビット環境では (種別, 値) の32ビットワードの組が並んでいる
+    let mut caps = (0, 0);
+    for entry in auxv.chunks_exact(8) {
+        let key = u32::from_ne_bytes(entry[..4].try_into().unwrap());
+        let value = u32::from_ne_bytes(entry[4..].try_into().unwrap());
+        match key {
+            AT_HWCAP => caps.0 = value,
+            AT_HWCAP2 => caps.1 = value,
+            _ => {}
+        }
+    }
+    caps
+}
+
+/// 補助ベクタを読めない環境では、拡張命令をすべて非対応として扱う
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn read_hwcaps() -> (u32, u32) {
+    (0, 0)
+}
+
+/// 実行中のCPUがNEON（Advanced SIMD）に対応しているかどうか
+pub fn neon_supported() -> bool {
+    hwcaps().0 & HWCAP_NEON != 0
+}
+
+/// 実行中のCPUがAArch32のSHA-256命令に対応しているかどうか
+pub fn is_supported() -> bool {
+    hwcaps().1 & HWCAP2_SHA2 != 0
+}
+
+/// 基本命令セットのみを使用する圧縮関数（すべてのARMv7で実行できる）
+pub fn compress256_scalar(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    let mut wk = [0u32; 64];
+    for block in blocks {
+        // SAFETY: 基本命令セットのみを使用し、wk・block・state の範囲内だけを読み書きする
+        unsafe {
+            schedule(&mut wk, block);
+            rounds(state, &wk);
+        }
+    }
+}
+
+/// メッセージスケジュールをNEONで計算する圧縮関数
+/// NEONに対応していないCPUで呼び出した場合はパニックします。
+pub fn compress256_neon(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    assert!(neon_supported(), "compress256_neon: CPU does not support NEON");
+    let mut wk = [0u32; 64];
+    for block in blocks {
+        // SAFETY: NEONが利用できることを直前に確認している
+        unsafe {
+            schedule_neon(&mut wk, block);
+            rounds(state, &wk);
+        }
+    }
+}
+
+/// 外部公開用の圧縮関数インターフェース（SHA-256命令を使用）
+/// SHA-256命令に対応していないCPUで呼び出した場合はパニックします。
+pub fn compress256(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    assert!(is_supported(), "compress256: CPU does not support the SHA-256 instructions");
+    if blocks.is_empty() {
+        return;
+    }
+    // SAFETY: SHA-256命令（およびそれが前提とするNEON）が利用できることを直前に確認している
+    unsafe { sha256_compress(state, blocks) }
+}
+
+/// メッセージスケジュール W[0..63] を計算し、K[t] を加算した値を wk に格納する
+unsafe fn schedule(wk: &mut [u32; 64], block: &[u8; 64]) {
+    asm!(
+        // W[0..15]: ビッグエンディアンのワードを読み込む
+        "mov {n}, #16",
+        "2:",
+        "ldr {x}, [{data}], #4",
+        "rev {x}, {x}",
+        "str {x}, [{w}], #4",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        // W[t] = W[t-16] + σ0(W[t-15]) + W[t-7] + σ1(W[t-2])
+        "mov {n}, #48",
+        "2:",
+        "ldr {x}, [{w}, #-60]",
+        "ror {s}, {x}, #7",
+        "eor {s}, {s}, {x}, ror #18",
+        "eor {s}, {s}, {x}, lsr #3",
+        "ldr {x}, [{w}, #-64]",
+        "add {x}, {x}, {s}",
+        "ldr {y}, [{w}, #-8]",
+        "ror {s}, {y}, #17",
+        "eor {s}, {s}, {y}, ror #19",
+        "eor {s}, {s}, {y}, lsr #10",
+        "add {x}, {x}, {s}",
+        "ldr {y}, [{w}, #-28]",
+        "add {x}, {x}, {y}",
+        "str {x}, [{w}], #4",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        // W[t] + K[t] で置き換える
+        "sub {w}, {w}, #256",
+        "mov {n}, #64",
+        "2:",
+        "ldr {x}, [{w}]",
+        "ldr {y}, [{k}], #4",
+        "add {x}, {x}, {y}",
+        "str {x}, [{w}], #4",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        w = inout(reg) wk.as_mut_ptr() => _,
+        data = inout(reg) block.as_ptr() => _,
+        k = inout(reg) K.as_ptr() => _,
+        n = out(reg) _,
+        x = out(reg) _,
+        y = out(reg) _,
+        s = out(reg) _,
+        options(nostack),
+    );
+}
+
+/// NEONでメッセージスケジュールを計算し、K[t] を加算した値を wk に格納する
+unsafe fn schedule_neon(wk: &mut [u32; 64], block: &[u8; 64]) {
+    asm!(
+        ".fpu neon",
+        // W[0..15]: 4ワードずつ読み込み、ワードごとにバイト順を反転する
+        "vld1.8 {{d0-d3}}, [{data}]!",
+        "vld1.8 {{d4-d7}}, [{data}]",
+        "vrev32.8 q0, q0",
+        "vrev32.8 q1, q1",
+        "vrev32.8 q2, q2",
+        "vrev32.8 q3, q3",
+        "vst1.32 {{d0-d3}}, [{w}]!",
+        "vst1.32 {{d4-d7}}, [{w}]!",
+        // W[t..t+3] を4ワードずつ計算する（q0〜q3 に W[t-16..t-1] を読み込む）
+        "mov {n}, #12",
+        "2:",
+        "sub {p}, {w}, #64",
+        "vld1.32 {{d0-d3}}, [{p}]!",
+        "vld1.32 {{d4-d7}}, [{p}]",
+        "vext.8 q4, q0, q1, #4",        // W[t-15..t-12]
+        "vext.8 q5, q2, q3, #4",        // W[t-7..t-4]
+        "vadd.i32 q0, q0, q5",
+        // σ0(x) = ROTR7(x) ^ ROTR18(x) ^ SHR3(x)
+        "vshr.u32 q5, q4, #7",
+        "vsli.32 q5, q4, #25",
+        "vshr.u32 q6, q4, #18",
+        "vsli.32 q6, q4, #14",
+        "veor q5, q5, q6",
+        "vshr.u32 q6, q4, #3",
+        "veor q5, q5, q6",
+        "vadd.i32 q0, q0, q5",
+        // σ1 は2ワード前の値に依存するため、下位2ワード（d0）と上位2ワード（d1）に分けて加算する
+        // σ1(x) = ROTR17(x) ^ ROTR19(x) ^ SHR10(x)
+        "vshr.u32 d8, d7, #17",         // d7 = W[t-2], W[t-1]
+        "vsli.32 d8, d7, #15",
+        "vshr.u32 d9, d7, #19",
+        "vsli.32 d9, d7, #13",
+        "veor d8, d8, d9",
+        "vshr.u32 d9, d7, #10",
+        "veor d8, d8, d9",
+        "vadd.i32 d0, d0, d8",
+        "vshr.u32 d8, d0, #17",         // d0 = W[t], W[t+1]
+        "vsli.32 d8, d0, #15",
+        "vshr.u32 d9, d0, #19",
+        "vsli.32 d9, d0, #13",
+        "veor d8, d8, d9",
+        "vshr.u32 d9, d0, #10",
+        "veor d8, d8, d9",
+        "vadd.i32 d1, d1, d8",
+        "vst1.32 {{d0, d1}}, [{w}]!",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        // W[t] + K[t] で置き換える
+        "sub {w}, {w}, #256",
+        "mov {n}, #16",
+        "2:",
+        "vld1.32 {{d0, d1}}, [{w}]",
+        "vld1.32 {{d2, d3}}, [{k}]!",
+        "vadd.i32 q0, q0, q1",
+        "vst1.32 {{d0, d1}}, [{w}]!",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        ".fpu vfpv3-d16",
+        w = inout(reg) wk.as_mut_ptr() => _,
+        data = inout(reg) block.as_ptr() => _,
+        k = inout(reg) K.as_ptr() => _,
+        n = out(reg) _,
+        p = out(reg) _,
+        out("d0") _, out("d1") _, out("d2") _, out("d3") _,
+        out("d4") _, out("d5") _, out("d6") _, out("d7") _,
+        out("d8") _, out("d9") _, out("d10") _, out("d11") _,
+        out("d12") _, out("d13") _,
+        options(nostack),
+    );
+}
+
+/// 1ラウンド分の命令列
+/// h に T1 + T2 を求めて次の a とし、d に T1 を加算して次の e とします。
+macro_rules! round {
+    ($a:literal, $b:literal, $c:literal, $d:literal,
+     $e:literal, $f:literal, $g:literal, $h:literal) => {
+        concat!(
+            // h += Σ1(e) = ROTR6(e ^ ROTR5(e) ^ ROTR19(e))
+            "eor {t1}, ", $e, ", ", $e, ", ror #5\n",
+            "eor {t1}, {t1}, ", $e, ", ror #19\n",
+            "add ", $h, ", ", $h, ", {t1}, ror #6\n",
+            // h += Ch(e, f, g) = g ^ (e & (f ^ g))
+            "eor {t1}, ", $f, ", ", $g, "\n",
+            "and {t1}, {t1}, ", $e, "\n",
+            "eor {t1}, {t1}, ", $g, "\n",
+            "add ", $h, ", ", $h, ", {t1}\n",
+            // h += W[t] + K[t]
+            "ldr {t1}, [{wk}], #4\n",
+            "add ", $h, ", ", $h, ", {t1}\n",
+            // d += T1
+            "add ", $d, ", ", $d, ", ", $h, "\n",
+            // h += Σ0(a) = ROTR2(a ^ ROTR11(a) ^ ROTR20(a))
+            "eor {t1}, ", $a, ", ", $a, ", ror #11\n",
+            "eor {t1}, {t1}, ", $a, ", ror #20\n",
+            "add ", $h, ", ", $h, ", {t1}, ror #2\n",
+            // h += Maj(a, b, c) = (a & b) | (c & (a | b))
+            "orr {t1}, ", $a, ", ", $b, "\n",
+            "and {t1}, {t1}, ", $c, "\n",
+            "and {t2}, ", $a, ", ", $b, "\n",
+            "orr {t1}, {t1}, {t2}\n",
+            "add ", $h, ", ", $h, ", {t1}\n",
+        )
+    };
+}
+
+/// 8ラウンド分の命令列（ラウンドごとに変数名をずらし、レジスタ間の移動を省く）
+macro_rules! rounds8 {
+    () => {
+        concat!(
+            round!("{a}", "{b}", "{c}", "{d}", "{e}", "{f}", "{g}", "{h}"),
+            round!("{h}", "{a}", "{b}", "{c}", "{d}", "{e}", "{f}", "{g}"),
+            round!("{g}", "{h}", "{a}", "{b}", "{c}", "{d}", "{e}", "{f}"),
+            round!("{f}", "{g}", "{h}", "{a}", "{b}", "{c}", "{d}", "{e}"),
+            round!("{e}", "{f}", "{g}", "{h}", "{a}", "{b}", "{c}", "{d}"),
+            round!("{d}", "{e}", "{f}", "{g}", "{h}", "{a}", "{b}", "{c}"),
+            round!("{c}", "{d}", "{e}", "{f}", "{g}", "{h}", "{a}", "{b}"),
+            round!("{b}", "{c}", "{d}", "{e}", "{f}", "{g}", "{h}", "{a}"),
+        )
+    };
+}
+
+/// W[t] + K[t] を使って64ラウンドを処理し、処理前の状態に加算する
+/// 作業変数 a〜h と一時変数2本をすべて汎用レジスタに置くため、ループせずに展開しています。
+unsafe fn rounds(state: &mut [u32; 8], wk: &[u32; 64]) {
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    asm!(
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        rounds8!(),
+        a = inout(reg) a,
+        b = inout(reg) b,
+        c = inout(reg) c,
+        d = inout(reg) d,
+        e = inout(reg) e,
+        f = inout(reg) f,
+        g = inout(reg) g,
+        h = inout(reg) h,
+        wk = inout(reg) wk.as_ptr() => _,
+        t1 = out(reg) _,
+        t2 = out(reg) _,
+        options(nostack, readonly, preserves_flags),
+    );
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// 4ラウンド分の命令列（q3 に W + K を求め、q0 = ABCD, q1 = EFGH を更新する）
+macro_rules! rounds4 {
+    ($w:literal) => {
+        concat!(
+            "vld1.32 {{d6, d7}}, [{k}]!\n",
+            "vadd.i32 q3, q3, ", $w, "\n",
+            "vmov q2, q0\n",
+            "sha256h.32 q0, q1, q3\n",
+            "sha256h2.32 q1, q2, q3\n",
+        )
+    };
+}
+
+/// メッセージを拡張しつつ4ラウンド分を計算する命令列
+/// w0〜w3 はそれぞれ W[t-16..t-12]、W[t-12..t-8]、W[t-8..t-4]、W[t-4..t] で、w0 を W[t..t+4] に更新します。
+macro_rules! schedule_rounds4 {
+    ($w0:literal, $w1:literal, $w2:literal, $w3:literal) => {
+        concat!(
+            "sha256su0.32 ", $w0, ", ", $w1, "\n",
+            "sha256su1.32 ", $w0, ", ", $w2, ", ", $w3, "\n",
+            rounds4!($w0),
+        )
+    };
+}
+
+/// AArch32 SHA-256命令を使用したメインの圧縮ロジック（blocks は空でないこと）
+unsafe fn sha256_compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    asm!(
+        ".arch armv8-a",
+        ".fpu crypto-neon-fp-armv8",
+        // q0 = ABCD, q1 = EFGH
+        "vld1.32 {{d0-d3}}, [{state}]",
+        "2:",
+        // メッセージブロックを q4〜q7 に読み込み、ワードごとにバイト順を反転する
+        "vld1.8 {{d8-d11}}, [{data}]!",
+        "vld1.8 {{d12-d15}}, [{data}]!",
+        "vrev32.8 q4, q4",
+        "vrev32.8 q5, q5",
+        "vrev32.8 q6, q6",
+        "vrev32.8 q7, q7",
+        "mov {k}, {ktab}",
+        // 最初の16ラウンド（入力メッセージをそのまま使用）
+        rounds4!("q4"),
+        rounds4!("q5"),
+        rounds4!("q6"),
+        rounds4!("q7"),
+        // 残りの48ラウンド（4本のレジスタを循環させてメッセージを拡張）
+        schedule_rounds4!("q4", "q5", "q6", "q7"),
+        schedule_rounds4!("q5", "q6", "q7", "q4"),
+        schedule_rounds4!("q6", "q7", "q4", "q5"),
+        schedule_rounds4!("q7", "q4", "q5", "q6"),
+        schedule_rounds4!("q4", "q5", "q6", "q7"),
+        schedule_rounds4!("q5", "q6", "q7", "q4"),
+        schedule_rounds4!("q6", "q7", "q4", "q5"),
+        schedule_rounds4!("q7", "q4", "q5", "q6"),
+        schedule_rounds4!("q4", "q5", "q6", "q7"),
+        schedule_rounds4!("q5", "q6", "q7", "q4"),
+        schedule_rounds4!("q6", "q7", "q4", "q5"),
+        schedule_rounds4!("q7", "q4", "q5", "q6"),
+        // メモリ上の処理前の状態を加算して書き戻す
+        "vld1.32 {{d4-d7}}, [{state}]",
+        "vadd.i32 q0, q0, q2",
+        "vadd.i32 q1, q1, q3",
+        "vst1.32 {{d0-d3}}, [{state}]",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        ".arch armv7-a",
+        ".fpu vfpv3-d16",
+        state = in(reg) state.as_mut_ptr(),
+        data = inout(reg) blocks.as_ptr() => _,
+        n = inout(reg) blocks.len() => _,
+        ktab = in(reg) K.as_ptr(),
+        k = out(reg) _,
+        out("d0") _, out("d1") _, out("d2") _, out("d3") _,
+        out("d4") _, out("d5") _, out("d6") _, out("d7") _,
+        out("d8") _, out("d9") _, out("d10") _, out("d11") _,
+        out("d12") _, out("d13") _, out("d14") _, out("d15") _,
+        options(nostack),
+    );
+}
+
+/// 基本命令セットのみを使用するバックエンド
+pub struct Arm;
+
+impl BlockCompressor for Arm {
+    fn name(&self) -> &'static str {
+        "arm"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        compress256_scalar(&mut state.h, blocks);
+    }
+}
+
+/// メッセージスケジュールをNEONで計算するバックエンド
+pub struct ArmNeon;
+
+impl BlockCompressor for ArmNeon {
+    fn name(&self) -> &'static str {
+        "arm-neon"
+    }
+
+    fn is_available(&self) -> bool {
+        neon_supported()
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        compress256_neon(&mut state.h, blocks);
+    }
+}
+
+/// AArch32 SHA-256命令を使用するバックエンド
+pub struct ArmSha2;
+
+impl BlockCompressor for ArmSha2 {
+    fn name(&self) -> &'static str {
+        "arm-sha2"
+    }
+
+    fn is_available(&self) -> bool {
+        is_supported()
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        compress256(&mut state.h, blocks);
+    }
+}
+
+// qemu-user 上で実行できる（例: cargo test --target armv7-unknown-linux-gnueabihf、
+// ランナーは .cargo/config.toml を参照）。対応していない拡張命令のテストは省略する
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_arm_scalar() {
+        check_backend(&Arm);
+    }
+
+    #[test]
+    fn test_arm_neon() {
+        check_backend(&ArmNeon);
+    }
+
+    #[test]
+    fn test_arm_sha2() {
+        check_backend(&ArmSha2);
+    }
+}
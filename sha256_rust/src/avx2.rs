@@ -0,0 +1,95 @@
+// AVX2による8レーン並列のSHA-256圧縮関数（マルチバッファ方式）
+//
+// 256ビットレジスタの各32ビットレーンに別々のメッセージの状態を割り当て、
+// 8本の独立したブロックを1回の呼び出しで同時に圧縮します。
+// 1本のメッセージを速くするものではなく、多数の短いメッセージのスループットを上げるための実装です。
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::many::LANES;
+use crate::K;
+
+/// 実行中のCPUがAVX2に対応しているかどうか
+pub(crate) fn is_supported() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+/// 8レーンの32ビット右ローテート
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm256_or_si256(_mm256_srli_epi32::<$n>($x), _mm256_slli_epi32::<{ 32 - $n }>($x))
+    };
+}
+
+/// 8本のブロックをそれぞれ対応する状態に対して圧縮する
+///
+/// # Safety
+/// 実行中のCPUがAVX2に対応している必要があります。
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn transform8(states: &mut [[u32; 8]; LANES], blocks: &[[u8; 64]; LANES]) {
+    // 各ブロックのt番目のワードを8レーンに並べてメッセージスケジュールW[0..15]を作成
+    let mut w = [_mm256_setzero_si256(); 64];
+    for (t, wt) in w.iter_mut().take(16).enumerate() {
+        let word = |lane: usize| {
+            u32::from_be_bytes(blocks[lane][t * 4..t * 4 + 4].try_into().unwrap()) as i32
+        };
+        *wt = _mm256_setr_epi32(
+            word(0), word(1), word(2), word(3), word(4), word(5), word(6), word(7),
+        );
+    }
+
+    // メッセージスケジュールをW[16..63]まで拡張（σ0, σ1）
+    for t in 16..64 {
+        let x = w[t - 15];
+        let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(x, 7), rotr!(x, 18)), _mm256_srli_epi32::<3>(x));
+        let y = w[t - 2];
+        let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(y, 17), rotr!(y, 19)), _mm256_srli_epi32::<10>(y));
+        w[t] = _mm256_add_epi32(_mm256_add_epi32(w[t - 16], s0), _mm256_add_epi32(w[t - 7], s1));
+    }
+
+    // 各レーンの状態を「ワードごと」のレジスタへ転置して読み込む
+    let mut v = [_mm256_setzero_si256(); 8];
+    for (i, vi) in v.iter_mut().enumerate() {
+        let h = |lane: usize| states[lane][i] as i32;
+        *vi = _mm256_setr_epi32(h(0), h(1), h(2), h(3), h(4), h(5), h(6), h(7));
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = v;
+
+    // メインループ - 64ラウンドの圧縮処理を8レーン同時に実行
+    for t in 0..64 {
+        let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(e, 6), rotr!(e, 11)), rotr!(e, 25));
+        let ch = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+        let k = _mm256_set1_epi32(K[t] as i32);
+        let temp1 = _mm256_add_epi32(
+            _mm256_add_epi32(_mm256_add_epi32(h, s1), _mm256_add_epi32(ch, k)),
+            w[t],
+        );
+        let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(a, 2), rotr!(a, 13)), rotr!(a, 22));
+        let maj = _mm256_xor_si256(
+            _mm256_xor_si256(_mm256_and_si256(a, b), _mm256_and_si256(a, c)),
+            _mm256_and_si256(b, c),
+        );
+        let temp2 = _mm256_add_epi32(s0, maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = _mm256_add_epi32(d, temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = _mm256_add_epi32(temp1, temp2);
+    }
+
+    // 処理前の状態に加算し、各レーンの状態へ書き戻す
+    for (i, x) in [a, b, c, d, e, f, g, h].into_iter().enumerate() {
+        let mut words = [0u32; LANES];
+        _mm256_storeu_si256(words.as_mut_ptr() as *mut __m256i, _mm256_add_epi32(x, v[i]));
+        for (lane, &word) in words.iter().enumerate() {
+            states[lane][i] = word;
+        }
+    }
+}
@@ -0,0 +1,200 @@
+// 圧縮関数のバックエンド共通インターフェースと、実行時のバックエンド選択
+//
+// 各バックエンド（汎用実装・CPU固有命令を使う実装）は `BlockCompressor` を実装し、
+// `BACKENDS` に優先順位の高い順で登録します。`best_backend` は実行中のCPUで利用できる
+// 最初のバックエンドを一度だけ判定してキャッシュし、`Sha256::new` はそれを使用します。
+
+use std::sync::OnceLock;
+
+use crate::{sha256_transform_generic, Sha256State};
+
+/// 64バイトブロックの列をまとめて処理する圧縮関数のバックエンド
+pub trait BlockCompressor: Sync {
+    /// ベンチマーク結果などに表示するバックエンド名
+    fn name(&self) -> &'static str;
+
+    /// 実行中のCPUでこのバックエンドが利用できるかどうか
+    fn is_available(&self) -> bool;
+
+    /// `blocks` を先頭から順に圧縮し、`state` を更新する
+    /// 利用できないCPUで呼び出した場合はパニックします（未定義動作にはなりません）。
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]);
+}
+
+/// `sha256_transform_generic` を使用する汎用実装（すべての環境で利用可能）
+pub struct Generic;
+
+impl BlockCompressor for Generic {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        for block in blocks {
+            sha256_transform_generic(state, block);
+        }
+    }
+}
+
+/// 登録済みのバックエンド（優先順位の高い順、最後は必ず汎用実装）
+///
+/// CPU固有命令を使うものを先に、CPU固有命令を使わない unrolled を汎用実装の直前に置きます。
+/// x86-avx / x86-ssse3 は unrolled と同じ展開済みのラウンドにSIMDのスケジュール計算を重ねたもので、
+/// SHA-NIを持たないx86ではこれらが自動選択されます。
+static BACKENDS: &[&dyn BlockCompressor] = &[
+    #[cfg(target_arch = "aarch64")]
+    &crate::aarch64::Aarch64Sha2,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    &crate::x86::ShaNi,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    &crate::ssse3::Avx,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    &crate::ssse3::Ssse3,
+    #[cfg(target_arch = "arm")]
+    &crate::arm::ArmSha2,
+    #[cfg(target_arch = "arm")]
+    &crate::arm::ArmNeon,
+    #[cfg(target_arch = "arm")]
+    &crate::arm::Arm,
+    &crate::unrolled::Unrolled,
+    &Generic,
+];
+
+/// このビルドに含まれるすべてのバックエンドを優先順位の高い順に返す
+/// 実行中のCPUで利用できないものも含まれるため、使用前に `is_available` を確認してください。
+pub fn backends() -> &'static [&'static dyn BlockCompressor] {
+    BACKENDS
+}
+
+/// 実行中のCPUで利用できる最速のバックエンドを返す（判定結果はプロセス内でキャッシュされる）
+pub fn best_backend() -> &'static dyn BlockCompressor {
+    static BEST: OnceLock<&'static dyn BlockCompressor> = OnceLock::new();
+    *BEST.get_or_init(|| {
+        BACKENDS
+            .iter()
+            .copied()
+            .find(|backend| backend.is_available())
+            .unwrap_or(&Generic)
+    })
+}
+
+/// 各バックエンドのテストで共通に使用する、汎用実装との比較
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::{BlockCompressor, Generic};
+    use crate::Sha256State;
+
+    /// 線形合同法による再現可能な乱数ブロック列
+    pub(crate) fn random_blocks(count: usize, mut seed: u32) -> Vec<[u8; 64]> {
+        (0..count)
+            .map(|_| {
+                std::array::from_fn(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (seed >> 16) as u8
+                })
+            })
+            .collect()
+    }
+
+    /// `backend` が汎用実装と同じ結果になることを確認する（実行中のCPUで利用できない場合は省略）
+    /// "abc" の1ブロック（FIPS 180-4 の例）と、乱数ブロック列を1ブロックずつ・複数ブロックまとめて処理した結果を比較します。
+    pub(crate) fn check_backend(backend: &dyn BlockCompressor) {
+        let name = backend.name();
+        if !backend.is_available() {
+            eprintln!("{} に非対応のため省略", name);
+            return;
+        }
+
+        let mut block = [0u8; 64];
+        block[..4].copy_from_slice(b"abc\x80");
+        block[63] = 0x18;
+        let mut state = Sha256State::new();
+        backend.compress(&mut state, &[block]);
+        assert_eq!(
+            state.h,
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223,
+                0xb00361a3, 0x96177a9c, 0xb410ff61, 0xf20015ad,
+            ],
+            "{}",
+            name
+        );
+
+        let blocks = random_blocks(1024, 0x5eed_0256);
+        let mut expected = Sha256State::new();
+        let mut state = Sha256State::new();
+        for block in &blocks {
+            Generic.compress(&mut expected, std::slice::from_ref(block));
+            backend.compress(&mut state, std::slice::from_ref(block));
+            assert_eq!(state.h, expected.h, "{}", name);
+        }
+
+        for count in [0, 1, 2, 3, 5, 17, 100, blocks.len()] {
+            let mut expected = Sha256State::new();
+            for block in &blocks[..count] {
+                Generic.compress(&mut expected, std::slice::from_ref(block));
+            }
+            let mut state = Sha256State::new();
+            backend.compress(&mut state, &blocks[..count]);
+            assert_eq!(state.h, expected.h, "{} ({} blocks)", name, count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sha256;
+
+    // 利用可能なすべてのバックエンドが汎用実装と同じ結果になること
+    #[test]
+    fn test_backends_match_generic() {
+        for &backend in backends() {
+            testing::check_backend(backend);
+        }
+    }
+
+    // 自動選択されたバックエンドは利用可能であり、Sha256::new で使用されること
+    #[test]
+    fn test_best_backend_is_used_by_default() {
+        let best = best_backend();
+        assert!(best.is_available());
+        assert_eq!(Sha256::new().backend().name(), best.name());
+    }
+
+    // CPU固有命令を使わないバックエンドでは unrolled が汎用実装より優先され、汎用実装が最後であること
+    #[test]
+    fn test_unrolled_ranks_above_generic() {
+        let names: Vec<&str> = backends().iter().map(|backend| backend.name()).collect();
+        assert_eq!(names[names.len() - 2..], ["unrolled", "generic"]);
+    }
+
+    // SIMDでスケジュールを計算するバックエンドは unrolled より優先されること
+    // （SHA-NIを持たないx86で自動選択される）
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_simd_schedule_ranks_above_unrolled() {
+        let position = |name| backends().iter().position(|backend| backend.name() == name).unwrap();
+        assert!(position("x86-sha-ni") < position("x86-avx"));
+        assert!(position("x86-avx") < position("x86-ssse3"));
+        assert!(position("x86-ssse3") < position("unrolled"));
+    }
+
+    // バックエンドを明示的に指定しても同じハッシュ値になること
+    #[test]
+    fn test_with_backend() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 253) as u8).collect();
+        let mut expected = Sha256::new();
+        expected.update(&data);
+        let expected = expected.finalize();
+
+        let mut hasher = Sha256::with_backend(&Generic).unwrap();
+        hasher.update(&data);
+        assert_eq!(hasher.backend().name(), "generic");
+        assert_eq!(hasher.finalize(), expected);
+    }
+}
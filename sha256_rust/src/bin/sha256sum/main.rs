@@ -0,0 +1,97 @@
+// sha256sum互換のファイルハッシュ計算コマンド
+// GNU coreutils の sha256sum と同じ形式（テキスト・バイナリ・BSDタグ形式・NUL区切り）で出力し、
+// --check でチェックサム一覧を検証します。
+// 引数解析・出力・検証の処理は checksum_cli クレートで sha256sum と sha512sum に共通化しています。
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use checksum_cli::{read_chunks, ChecksumAlgorithm};
+use sha256_arm::{Sha224, Sha256};
+
+/// 検証モードで扱うアルゴリズム（出力モードでは常にSHA-256）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Sha224,
+    Sha256,
+}
+
+impl ChecksumAlgorithm for Algorithm {
+    const DEFAULT: Algorithm = Algorithm::Sha256;
+    const ALL: &'static [Algorithm] = &[Algorithm::Sha224, Algorithm::Sha256];
+
+    fn tag(self) -> &'static str {
+        match self {
+            Algorithm::Sha224 => "SHA224",
+            Algorithm::Sha256 => "SHA256",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            Algorithm::Sha224 => 56,
+            Algorithm::Sha256 => 64,
+        }
+    }
+
+    fn from_hex_len(len: usize) -> Option<Self> {
+        Self::ALL.iter().copied().find(|algorithm| algorithm.hex_len() == len)
+    }
+
+    fn digest<R: Read>(self, reader: R) -> io::Result<Vec<u8>> {
+        match self {
+            Algorithm::Sha224 => {
+                let mut hasher = Sha224::new();
+                read_chunks(reader, |chunk| hasher.update(chunk))?;
+                Ok(hasher.finalize().to_vec())
+            }
+            Algorithm::Sha256 => digest_reader(reader).map(|digest| digest.to_vec()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    checksum_cli::run::<Algorithm>(
+        "sha256sum",
+        concat!("sha256sum (sha256_arm) ", env!("CARGO_PKG_VERSION")),
+        print_help,
+    )
+}
+
+/// リーダーから終端まで読み込み、SHA-256を計算する
+fn digest_reader<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+fn print_help(prog: &str) {
+    println!("Usage: {} [OPTION]... [FILE]...", prog);
+    println!("Print SHA256 (256-bit) checksums.");
+    println!();
+    println!("With no FILE, or when FILE is -, read standard input.");
+    println!("  -b, --binary          read in binary mode");
+    println!("  -c, --check           read checksums from the FILEs and check them");
+    println!("      --tag             create a BSD-style checksum");
+    println!("  -t, --text            read in text mode (default)");
+    println!("  -z, --zero            end each output line with NUL, not newline,");
+    println!("                          and disable file name escaping");
+    println!();
+    println!("The following five options are useful only when verifying checksums:");
+    println!("      --ignore-missing  don't fail or report status for missing files");
+    println!("      --quiet           don't print OK for each successfully verified file");
+    println!("      --status          don't output anything, status code shows success");
+    println!("      --strict          exit non-zero for improperly formatted checksum lines");
+    println!("  -w, --warn            warn about improperly formatted checksum lines");
+    println!();
+    println!("      --help        display this help and exit");
+    println!("      --version     output version information and exit");
+    println!();
+    println!("The sums are computed as described in FIPS-180-4.");
+    println!("The default mode is to print a line with: checksum, a space,");
+    println!("a character indicating input mode ('*' for binary, ' ' for text");
+    println!("or where binary is insignificant), and name for each FILE.");
+    println!();
+    println!("When checking, the input should be a former output of this program,");
+    println!("or equivalent standalone program.  SHA224 and SHA256 lines may be mixed.");
+}
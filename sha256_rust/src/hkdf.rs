@@ -0,0 +1,179 @@
+// HKDF-SHA256（RFC 5869）による鍵導出
+
+use crate::HmacSha256;
+
+/// SHA-256の出力長（HashLen）
+const HASH_LEN: usize = 32;
+
+/// HKDFの処理で発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HkdfError {
+    /// 要求された出力長が 255 * HashLen（8160バイト）を超えている
+    InvalidLength(usize),
+    /// 外部から与えられたPRKがHashLenより短い
+    InvalidPrkLength(usize),
+}
+
+impl std::fmt::Display for HkdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HkdfError::InvalidLength(len) => write!(
+                f,
+                "HKDF: output length {} exceeds the maximum of {} bytes",
+                len,
+                255 * HASH_LEN
+            ),
+            HkdfError::InvalidPrkLength(len) => write!(
+                f,
+                "HKDF: PRK length {} is shorter than the hash length {}",
+                len, HASH_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HkdfError {}
+
+/// HKDF-SHA256のコンテキスト
+///
+/// Extract段階で得た擬似乱数鍵（PRK）でHMACを鍵付けした状態を保持し、
+/// Expand段階では任意の `info` に対して何度でも鍵素材を導出できます。
+#[derive(Clone)]
+pub struct Hkdf {
+    prk: Vec<u8>,
+    hmac: HmacSha256,   // PRKで鍵付け済みのHMAC
+}
+
+impl Hkdf {
+    /// Extract段階: `salt` と入力鍵素材 `ikm` からPRKを計算
+    /// `salt` が `None` の場合は、HashLenバイトの0をソルトとして使用します。
+    pub fn extract(salt: Option<&[u8]>, ikm: &[u8]) -> Self {
+        let mut mac = HmacSha256::new(salt.unwrap_or(&[0u8; HASH_LEN]));
+        mac.update(ikm);
+        let prk = mac.finalize();
+
+        Self {
+            prk: prk.to_vec(),
+            hmac: HmacSha256::new(&prk),
+        }
+    }
+
+    /// 既に計算済みのPRKからコンテキストを作成（Extract段階を省略する場合）
+    pub fn from_prk(prk: &[u8]) -> Result<Self, HkdfError> {
+        if prk.len() < HASH_LEN {
+            return Err(HkdfError::InvalidPrkLength(prk.len()));
+        }
+
+        Ok(Self {
+            prk: prk.to_vec(),
+            hmac: HmacSha256::new(prk),
+        })
+    }
+
+    /// 擬似乱数鍵（PRK）を返す
+    pub fn prk(&self) -> &[u8] {
+        &self.prk
+    }
+
+    /// Expand段階: `info` に紐づく鍵素材で `okm` 全体を埋める
+    /// `okm` の長さが 255 * HashLen を超える場合はエラーを返し、`okm` は変更しません。
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), HkdfError> {
+        if okm.len() > 255 * HASH_LEN {
+            return Err(HkdfError::InvalidLength(okm.len()));
+        }
+
+        // T(i) = HMAC(PRK, T(i-1) || info || i) を連結して出力する
+        let mut prev = [0u8; HASH_LEN];
+        for (i, chunk) in okm.chunks_mut(HASH_LEN).enumerate() {
+            let mut mac = self.hmac.clone();
+            if i > 0 {
+                mac.update(&prev);
+            }
+            mac.update(info);
+            mac.update(&[(i + 1) as u8]);
+            prev = mac.finalize();
+
+            chunk.copy_from_slice(&prev[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    // RFC 5869 テストケース1（基本的なテスト）
+    #[test]
+    fn test_hkdf_sha256_rfc5869_case1() {
+        let hkdf = Hkdf::extract(Some(&hex("000102030405060708090a0b0c")), &[0x0b; 22]);
+        assert_eq!(hkdf.prk(), hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"));
+
+        let mut okm = [0u8; 42];
+        hkdf.expand(&hex("f0f1f2f3f4f5f6f7f8f9"), &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+        );
+    }
+
+    // RFC 5869 テストケース2（長い入力・出力）
+    #[test]
+    fn test_hkdf_sha256_rfc5869_case2() {
+        let salt: Vec<u8> = (0x60..=0xaf).collect();
+        let ikm: Vec<u8> = (0x00..=0x4f).collect();
+        let info: Vec<u8> = (0xb0..=0xff).collect();
+
+        let hkdf = Hkdf::extract(Some(&salt), &ikm);
+        assert_eq!(hkdf.prk(), hex("06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244"));
+
+        let mut okm = [0u8; 82];
+        hkdf.expand(&info, &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                 59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                 cc30c58179ec3e87c14c01d5c1f3434f1d87")
+        );
+    }
+
+    // RFC 5869 テストケース3（ソルト・infoが空）
+    #[test]
+    fn test_hkdf_sha256_rfc5869_case3() {
+        let hkdf = Hkdf::extract(Some(&[]), &[0x0b; 22]);
+        assert_eq!(hkdf.prk(), hex("19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04"));
+
+        let mut okm = [0u8; 42];
+        hkdf.expand(&[], &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8")
+        );
+
+        // ソルト省略時は HashLen バイトの0と同じ結果になる
+        let unsalted = Hkdf::extract(None, &[0x0b; 22]);
+        assert_eq!(unsalted.prk(), hkdf.prk());
+    }
+
+    // 出力長の上限（255 * HashLen）を超えるとエラーになること
+    #[test]
+    fn test_hkdf_sha256_length_limit() {
+        let hkdf = Hkdf::extract(None, b"input key material");
+
+        let mut max = vec![0u8; 255 * HASH_LEN];
+        assert!(hkdf.expand(b"info", &mut max).is_ok());
+
+        let mut too_long = vec![0u8; 255 * HASH_LEN + 1];
+        assert_eq!(
+            hkdf.expand(b"info", &mut too_long),
+            Err(HkdfError::InvalidLength(255 * HASH_LEN + 1))
+        );
+        assert!(too_long.iter().all(|&b| b == 0));
+
+        assert_eq!(
+            Hkdf::from_prk(&[0u8; HASH_LEN - 1]).err(),
+            Some(HkdfError::InvalidPrkLength(HASH_LEN - 1))
+        );
+    }
+}
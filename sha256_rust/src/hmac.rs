@@ -0,0 +1,155 @@
+// HMAC-SHA256（RFC 2104）による鍵付きハッシュ
+
+use crate::Sha256;
+
+/// SHA-256のブロック長（バイト）
+const BLOCK_LEN: usize = 64;
+
+/// ストリーミング対応のHMAC-SHA256コンテキスト
+///
+/// 鍵から導出した内側（ipad）・外側（opad）のハッシュ状態を生成時に計算して保持します。
+/// 同じ鍵で複数のメッセージを処理する場合は、生成済みのコンテキストを `clone` して使い回せます。
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,   // 鍵 XOR ipad を処理済みの内側ハッシュ
+    outer: Sha256,   // 鍵 XOR opad を処理済みの外側ハッシュ
+}
+
+impl HmacSha256 {
+    /// 任意の長さの鍵からHMACコンテキストを作成
+    /// ブロック長（64バイト）を超える鍵は、先にSHA-256でハッシュ化してから使用します。
+    pub fn new(key: &[u8]) -> Self {
+        let mut key_block = [0u8; BLOCK_LEN];
+        if key.len() > BLOCK_LEN {
+            let mut hasher = Sha256::new();
+            hasher.update(key);
+            key_block[..32].copy_from_slice(&hasher.finalize());
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        // 内側パッド (0x36) と外側パッド (0x5c) を鍵にXORしたブロックを作成
+        let mut ipad = [0x36u8; BLOCK_LEN];
+        let mut opad = [0x5cu8; BLOCK_LEN];
+        for i in 0..BLOCK_LEN {
+            ipad[i] ^= key_block[i];
+            opad[i] ^= key_block[i];
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(&ipad);
+        let mut outer = Sha256::new();
+        outer.update(&opad);
+
+        Self { inner, outer }
+    }
+
+    /// メッセージデータを供給し、内側ハッシュを更新
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// 32バイトの認証タグを出力
+    pub fn finalize(self) -> [u8; 32] {
+        let inner_hash = self.inner.finalize();
+        let mut outer = self.outer;
+        outer.update(&inner_hash);
+        outer.finalize()
+    }
+
+    /// 計算したタグと与えられたタグを定数時間で比較
+    /// 長さが異なる場合は常に `false` を返します。
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time_eq(&self.finalize(), tag)
+    }
+}
+
+/// タイミング攻撃を避けるため、内容によらず全バイトを比較する
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut mac = HmacSha256::new(key);
+        mac.update(data);
+        mac.finalize()
+    }
+
+    // RFC 4231 テストケース1
+    #[test]
+    fn test_hmac_sha256_rfc4231_case1() {
+        let result = hmac(&[0x0b; 20], b"Hi There");
+        assert_eq!(result.to_vec(), hex("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"));
+    }
+
+    // RFC 4231 テストケース2（鍵長が出力長より短い場合）
+    #[test]
+    fn test_hmac_sha256_rfc4231_case2() {
+        let result = hmac(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(result.to_vec(), hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"));
+    }
+
+    // RFC 4231 テストケース3
+    #[test]
+    fn test_hmac_sha256_rfc4231_case3() {
+        let result = hmac(&[0xaa; 20], &[0xdd; 50]);
+        assert_eq!(result.to_vec(), hex("773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe"));
+    }
+
+    // RFC 4231 テストケース4
+    #[test]
+    fn test_hmac_sha256_rfc4231_case4() {
+        let key: Vec<u8> = (0x01..=0x19).collect();
+        let result = hmac(&key, &[0xcd; 50]);
+        assert_eq!(result.to_vec(), hex("82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b"));
+    }
+
+    // RFC 4231 テストケース5（出力を128ビットに切り詰め）
+    #[test]
+    fn test_hmac_sha256_rfc4231_case5() {
+        let result = hmac(&[0x0c; 20], b"Test With Truncation");
+        assert_eq!(result[..16].to_vec(), hex("a3b6167473100ee06e0c796c2955552b"));
+    }
+
+    // RFC 4231 テストケース6（ブロック長を超える鍵）
+    #[test]
+    fn test_hmac_sha256_rfc4231_case6() {
+        let result = hmac(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(result.to_vec(), hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"));
+    }
+
+    // RFC 4231 テストケース7（ブロック長を超える鍵とデータ）
+    #[test]
+    fn test_hmac_sha256_rfc4231_case7() {
+        let result = hmac(
+            &[0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size data. \
+              The key needs to be hashed before being used by the HMAC algorithm.",
+        );
+        assert_eq!(result.to_vec(), hex("9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"));
+    }
+
+    // 分割して供給しても一括の場合と同じタグになり、verifyが正しく判定すること
+    #[test]
+    fn test_hmac_sha256_streaming_and_verify() {
+        let mut mac = HmacSha256::new(b"Jefe");
+        mac.update(b"what do ya ");
+        mac.update(b"want for nothing?");
+        let expected = hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        assert!(mac.clone().verify(&expected));
+
+        let mut wrong = expected.clone();
+        wrong[31] ^= 1;
+        assert!(!mac.clone().verify(&wrong));
+        assert!(!mac.verify(&expected[..16]));
+    }
+}
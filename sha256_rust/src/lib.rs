@@ -1,6 +1,40 @@
 // Rust用のSHA-256 ARMアセンブリ実装
 
-use core::arch::asm;
+#[cfg(target_arch = "aarch64")]
+pub mod aarch64;
+#[cfg(target_arch = "arm")]
+pub mod arm;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+mod backend;
+mod hkdf;
+mod hmac;
+mod io;
+mod many;
+mod pbkdf2;
+mod serialize;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod ssse3;
+mod unrolled;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod x86;
+
+#[cfg(target_arch = "aarch64")]
+pub use aarch64::Aarch64Sha2;
+#[cfg(target_arch = "arm")]
+pub use arm::{Arm, ArmNeon, ArmSha2};
+pub use backend::{backends, best_backend, BlockCompressor, Generic};
+pub use hkdf::{Hkdf, HkdfError};
+pub use hmac::HmacSha256;
+pub use io::{HashingReader, HashingWriter};
+pub use many::{hash_many, hash_many_with, ManyBackend};
+pub use pbkdf2::{pbkdf2, Pbkdf2Error};
+pub use serialize::StateError;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use ssse3::{Avx, Ssse3};
+pub use unrolled::Unrolled;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use x86::ShaNi;
 
 /// SHA-256の状態（32ビットワード × 8本）を保持する構造体
 #[repr(C)]
@@ -26,6 +60,29 @@ impl Sha256State {
             ],
         }
     }
+
+    /// SHA-224の初期化ベクトル（IV）で初期化（FIPS 180-4 §5.3.2）
+    /// 9番目から16番目の素数の平方根の小数部分（64ビット）の下位32ビットです。
+    pub fn new_224() -> Self {
+        Self {
+            h: [
+                0xc1059ed8,
+                0x367cd507,
+                0x3070dd17,
+                0xf70e5939,
+                0xffc00b31,
+                0x68581511,
+                0x64f98fa7,
+                0xbefa4fa4,
+            ],
+        }
+    }
+}
+
+impl Default for Sha256State {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// SHA-256の各ラウンドで使用される定数K
@@ -51,10 +108,13 @@ const K: [u32; 64] = [
 
 /// インラインアセンブリを使用したARM向けSHA-256変換関数
 /// 1回につき64バイト（512ビット）のブロックを処理します。
+/// ARMv7の基本命令セットのみを使用するため、どのARMv7でも実行できます（詳細は `arm` モジュールを参照）。
+///
+/// # Safety
+/// 呼び出し側が満たすべき前提条件はありません（従来のシグネチャとの互換性のため `unsafe` のままにしています）。
 #[cfg(target_arch = "arm")]
 pub unsafe fn sha256_transform_arm(state: &mut Sha256State, data: &[u8; 64]) {
-    // 汎用(Generic)の実装を呼び出しています。
-    sha256_transform_generic(state, data);
+    arm::compress256_scalar(&mut state.h, std::slice::from_ref(data));
 }
 
 /// 非ARMアーキテクチャでテストなどを行うための公開エクスポート
@@ -137,23 +197,47 @@ pub fn sha256_transform_generic(state: &mut Sha256State, data: &[u8; 64]) {
 }
 
 /// ハッシュ計算全体を管理するSHA-256コンテキスト
+#[derive(Clone)]
 pub struct Sha256 {
     state: Sha256State,
     buffer: [u8; 64],      // 未処理データを一時保存する64バイトバッファ
     buffer_len: usize,     // 現在バッファに入っているバイト数
     total_len: u64,        // これまでに処理したデータの総バイト数
+    backend: &'static dyn BlockCompressor,   // ブロックの圧縮に使用するバックエンド
 }
 
 impl Sha256 {
     /// 新規コンテキストを初期状態で作成
     pub fn new() -> Self {
+        Self::with_state(Sha256State::new())
+    }
+
+    /// 圧縮関数のバックエンドを指定してコンテキストを作成
+    /// 実行中のCPUでそのバックエンドが利用できない場合は `None` を返します。
+    pub fn with_backend(backend: &'static dyn BlockCompressor) -> Option<Self> {
+        if !backend.is_available() {
+            return None;
+        }
+        let mut hasher = Self::new();
+        hasher.backend = backend;
+        Some(hasher)
+    }
+
+    /// 任意の初期状態からコンテキストを作成（SHA-224などの派生アルゴリズム用）
+    fn with_state(state: Sha256State) -> Self {
         Self {
-            state: Sha256State::new(),
+            state,
             buffer: [0; 64],
             buffer_len: 0,
             total_len: 0,
+            backend: best_backend(),
         }
     }
+
+    /// このコンテキストが使用している圧縮関数のバックエンド
+    pub fn backend(&self) -> &'static dyn BlockCompressor {
+        self.backend
+    }
     
     /// 入力データを供給し、ハッシュ状態を更新
     pub fn update(&mut self, data: &[u8]) {
@@ -170,29 +254,38 @@ impl Sha256 {
             
             // バッファが一杯になったら変換を実行
             if self.buffer_len == 64 {
-                sha256_transform_generic(&mut self.state, &self.buffer);
+                self.backend.compress(&mut self.state, &[self.buffer]);
                 self.buffer_len = 0;
             }
         }
         
-        // 64バイトの完全なブロックをループで処理
-        while pos + 64 <= data.len() {
-            let mut block = [0u8; 64];
-            block.copy_from_slice(&data[pos..pos + 64]);
-            sha256_transform_generic(&mut self.state, &block);
-            pos += 64;
+        // 64バイトの完全なブロックが連続する部分は、入力スライスからコピーせずにまとめて圧縮
+        let (blocks, rest) = data[pos..].as_chunks::<64>();
+        if !blocks.is_empty() {
+            self.backend.compress(&mut self.state, blocks);
         }
         
         // 1ブロックに満たない残りのデータをバッファに保存
-        if pos < data.len() {
-            let remaining = data.len() - pos;
-            self.buffer[..remaining].copy_from_slice(&data[pos..]);
-            self.buffer_len = remaining;
+        if !rest.is_empty() {
+            self.buffer[..rest.len()].copy_from_slice(rest);
+            self.buffer_len = rest.len();
         }
     }
     
     /// パディングを追加し、最終的な32バイトのハッシュ値を出力
-    pub fn finalize(mut self) -> [u8; 32] {
+    pub fn finalize(self) -> [u8; 32] {
+        let state = self.finalize_state();
+        
+        // ハッシュ状態（8本のu32）をバイト配列に変換して出力
+        let mut result = [0u8; 32];
+        for i in 0..8 {
+            result[i * 4..(i + 1) * 4].copy_from_slice(&state.h[i].to_be_bytes());
+        }
+        result
+    }
+    
+    /// パディングと長さ情報を処理し、最終ブロック処理後の内部状態を返す
+    fn finalize_state(mut self) -> Sha256State {
         let bit_len = self.total_len * 8;
         
         // パディング開始: 最初のビットを1にする (0x80)
@@ -205,7 +298,7 @@ impl Sha256 {
                 self.buffer[self.buffer_len] = 0;
                 self.buffer_len += 1;
             }
-            sha256_transform_generic(&mut self.state, &self.buffer);
+            self.backend.compress(&mut self.state, &[self.buffer]);
             self.buffer_len = 0;
         }
         
@@ -217,17 +310,65 @@ impl Sha256 {
         
         // 最後の8バイトに総ビット長を書き込む（ビッグエンディアン）
         self.buffer[56..64].copy_from_slice(&bit_len.to_be_bytes());
-        sha256_transform_generic(&mut self.state, &self.buffer);
+        self.backend.compress(&mut self.state, &[self.buffer]);
         
-        // ハッシュ状態（8本のu32）をバイト配列に変換して出力
-        let mut result = [0u8; 32];
-        for i in 0..8 {
-            result[i * 4..(i + 1) * 4].copy_from_slice(&self.state.h[i].to_be_bytes());
+        self.state
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-224コンテキスト
+/// 圧縮関数はSHA-256と共通で、初期化ベクトルと出力長（28バイト）のみが異なります。
+#[derive(Clone)]
+pub struct Sha224 {
+    inner: Sha256,
+}
+
+impl Sha224 {
+    /// 新規コンテキストをSHA-224の初期状態で作成
+    pub fn new() -> Self {
+        Self {
+            inner: Sha256::with_state(Sha256State::new_224()),
+        }
+    }
+    
+    /// 入力データを供給し、ハッシュ状態を更新
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+    
+    /// パディングを追加し、先頭7ワード（28バイト）に切り詰めたハッシュ値を出力
+    pub fn finalize(self) -> [u8; 28] {
+        let state = self.inner.finalize_state();
+        
+        let mut result = [0u8; 28];
+        for i in 0..7 {
+            result[i * 4..(i + 1) * 4].copy_from_slice(&state.h[i].to_be_bytes());
         }
         result
     }
 }
 
+impl Default for Sha224 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// テストで使用する16進文字列のデコード（テストベクタの記述用）
+#[cfg(test)]
+pub(crate) fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,4 +406,92 @@ mod tests {
         
         assert_eq!(result, expected);
     }
-}
\ No newline at end of file
+    
+    // SHA-224: 空入力に対するテストベクトル
+    #[test]
+    fn test_sha224_empty() {
+        let mut hasher = Sha224::new();
+        hasher.update(b"");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0xd1, 0x4a, 0x02, 0x8c, 0x2a, 0x3a, 0x2b, 0xc9,
+            0x47, 0x61, 0x02, 0xbb, 0x28, 0x82, 0x34, 0xc4,
+            0x15, 0xa2, 0xb0, 0x1f, 0x82, 0x8e, 0xa6, 0x2a,
+            0xc5, 0xb3, 0xe4, 0x2f,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // SHA-224: 文字列 "abc" に対するテストベクトル（FIPS 180-4 付録）
+    #[test]
+    fn test_sha224_abc() {
+        let mut hasher = Sha224::new();
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22,
+            0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3,
+            0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7,
+            0xe3, 0x6c, 0x9d, 0xa7,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // SHA-224: 2ブロックにまたがる448ビットメッセージのテストベクトル
+    #[test]
+    fn test_sha224_two_blocks() {
+        let mut hasher = Sha224::new();
+        hasher.update(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x75, 0x38, 0x8b, 0x16, 0x51, 0x27, 0x76, 0xcc,
+            0x5d, 0xba, 0x5d, 0xa1, 0xfd, 0x89, 0x01, 0x50,
+            0xb0, 0xc6, 0x45, 0x5c, 0xb4, 0xf5, 0x8b, 0x19,
+            0x52, 0x52, 0x25, 0x25,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 100万文字の "a"（FIPS 180-4 のテストベクトル）を1回の update で処理する
+    #[test]
+    fn test_sha256_million_a() {
+        let mut hasher = Sha256::new();
+        hasher.update(&vec![b'a'; 1_000_000]);
+        let result = hasher.finalize();
+        
+        let expected = [
+            0xcd, 0xc7, 0x6e, 0x5c, 0x99, 0x14, 0xfb, 0x92,
+            0x81, 0xa1, 0xc7, 0xe2, 0x84, 0xd7, 0x3e, 0x67,
+            0xf1, 0x80, 0x9a, 0x48, 0xa4, 0x97, 0x20, 0x0e,
+            0x04, 0x6d, 0x39, 0xcc, 0xc7, 0x11, 0x2c, 0xd0,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 入力をどこで分割して update しても、まとめて渡した場合と同じ結果になること
+    #[test]
+    fn test_sha256_split_updates() {
+        let data: Vec<u8> = (0..64 * 5 + 17).map(|i| (i * 31 + 7) as u8).collect();
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        let expected = hasher.finalize();
+        
+        for first in [0, 1, 64 - 1, 64, 64 + 1, 64 * 2, 64 * 3 + 5] {
+            for second in [0, 1, 64, 64 * 2 - 3] {
+                let second = (first + second).min(data.len());
+                let mut hasher = Sha256::new();
+                hasher.update(&data[..first]);
+                hasher.update(&data[first..second]);
+                hasher.update(&data[second..]);
+                assert_eq!(hasher.finalize(), expected, "{} / {}", first, second);
+            }
+        }
+    }
+}
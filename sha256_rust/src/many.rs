@@ -0,0 +1,272 @@
+// 多数の独立したメッセージをまとめてハッシュするAPI
+//
+// AVX2が利用できる場合は、8本のメッセージを1本ずつレーンに割り当てて同時に圧縮します。
+// ただしSHA-NIを持つCPUでは、1本ずつSHA-NIで計算する方が8レーンのAVX2より速いため、そちらを使用します
+// （`sha256_test many` で計測: SHA-NIとAVX2を持つ Xeon で、0〜256バイトの10万メッセージに
+// SHA-NIの逐次計算は約26ミリ秒、AVX2の8レーンは約34ミリ秒）。
+// どちらの実装も `hash_many_with` で明示的に選択できます。
+// メッセージごとにパディング後のブロック数が異なるため、処理を終えたレーンには
+// 次のメッセージを順次割り当て、空きレーンが残らないようにしています。
+
+// レーン処理はAVX2を持つx86でのみ使用する（その他の環境ではテストからのみ使用）
+#![cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+
+use crate::{Sha256, Sha256State};
+
+/// `hash_many_with` で使用する実装
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManyBackend {
+    /// 各メッセージを `Sha256`（自動選択されたバックエンド）で順番に計算する（すべての環境で利用可能）
+    Serial,
+    /// AVX2で8本のメッセージを同時に圧縮する（AVX2を持つx86のみ）
+    Avx2,
+}
+
+impl ManyBackend {
+    /// ベンチマーク結果などに表示する名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManyBackend::Serial => "serial",
+            ManyBackend::Avx2 => "avx2-8way",
+        }
+    }
+
+    /// 実行中のCPUでこの実装が利用できるかどうか
+    pub fn is_available(&self) -> bool {
+        match self {
+            ManyBackend::Serial => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ManyBackend::Avx2 => crate::avx2::is_supported(),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            ManyBackend::Avx2 => false,
+        }
+    }
+
+    /// `hash_many` が使用する実装（AVX2があり、SHA-NIがない場合のみ8レーン）
+    pub fn best() -> ManyBackend {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if crate::avx2::is_supported() && !crate::x86::is_supported() {
+            return ManyBackend::Avx2;
+        }
+        ManyBackend::Serial
+    }
+}
+
+/// 複数のメッセージのSHA-256をまとめて計算する（結果は入力と同じ順序）
+///
+/// 実装は `ManyBackend::best` で選択します。AVX2が利用できない（またはSHA-NIが利用できる）環境では、
+/// 各メッセージを `Sha256` で順番に計算します。
+pub fn hash_many(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+    hash_many_with(ManyBackend::best(), messages).expect("選択した実装は常に利用できる")
+}
+
+/// 指定した実装で複数のメッセージのSHA-256をまとめて計算する
+/// 実行中のCPUで `backend` が利用できない場合は `None` を返します。
+pub fn hash_many_with(backend: ManyBackend, messages: &[&[u8]]) -> Option<Vec<[u8; 32]>> {
+    if !backend.is_available() {
+        return None;
+    }
+    match backend {
+        ManyBackend::Serial => Some(
+            messages
+                .iter()
+                .map(|message| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(message);
+                    hasher.finalize()
+                })
+                .collect(),
+        ),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        ManyBackend::Avx2 => {
+            // SAFETY: AVX2が利用できることを直前に確認している
+            Some(hash_lanes(messages, |states, blocks| unsafe {
+                crate::avx2::transform8(states, blocks)
+            }))
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        ManyBackend::Avx2 => None,
+    }
+}
+
+/// 同時に処理するメッセージ数（AVX2の8レーンに合わせる）
+pub(crate) const LANES: usize = 8;
+
+/// パディングを含めたブロック数（0x80 の1バイトと長さ情報の8バイトが必要）
+fn padded_blocks(len: usize) -> usize {
+    (len + 9).div_ceil(64)
+}
+
+/// パディング済みメッセージの `index` 番目のブロックを組み立てる
+fn padded_block(data: &[u8], index: usize, block: &mut [u8; 64]) {
+    let start = index * 64;
+    block.fill(0);
+
+    if start < data.len() {
+        let n = (data.len() - start).min(64);
+        block[..n].copy_from_slice(&data[start..start + n]);
+    }
+    // メッセージ末尾の直後に 0x80 を置く
+    if (start..start + 64).contains(&data.len()) {
+        block[data.len() - start] = 0x80;
+    }
+    // 最終ブロックの末尾8バイトに総ビット長を書き込む
+    if index + 1 == padded_blocks(data.len()) {
+        block[56..].copy_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    }
+}
+
+/// 各レーンで処理中のメッセージ
+struct Lane {
+    message: usize,   // 入力中の位置（結果の格納先）
+    block: usize,     // 次に処理するブロック番号
+}
+
+/// 8レーンの圧縮関数 `transform` を使って全メッセージを処理する
+fn hash_lanes<F>(messages: &[&[u8]], mut transform: F) -> Vec<[u8; 32]>
+where
+    F: FnMut(&mut [[u32; 8]; LANES], &[[u8; 64]; LANES]),
+{
+    let mut digests = vec![[0u8; 32]; messages.len()];
+    let mut states = [[0u32; 8]; LANES];
+    let mut blocks = [[0u8; 64]; LANES];
+    let mut lanes: [Option<Lane>; LANES] = Default::default();
+    let mut next = 0;
+
+    loop {
+        // 空いているレーンに次のメッセージを割り当てる
+        for (lane, state) in lanes.iter_mut().zip(states.iter_mut()) {
+            if lane.is_none() && next < messages.len() {
+                *lane = Some(Lane { message: next, block: 0 });
+                *state = Sha256State::new().h;
+                next += 1;
+            }
+        }
+        if lanes.iter().all(Option::is_none) {
+            break;
+        }
+
+        // 各レーンの次のブロックを用意する（空きレーンは結果を使わないため内容は任意）
+        for (lane, block) in lanes.iter().zip(blocks.iter_mut()) {
+            if let Some(lane) = lane {
+                padded_block(messages[lane.message], lane.block, block);
+            }
+        }
+        transform(&mut states, &blocks);
+
+        // 最終ブロックまで処理したレーンの結果を書き出して空ける
+        for (slot, state) in lanes.iter_mut().zip(states.iter()) {
+            let Some(lane) = slot else { continue };
+            lane.block += 1;
+            if lane.block == padded_blocks(messages[lane.message].len()) {
+                for (i, word) in state.iter().enumerate() {
+                    digests[lane.message][i * 4..(i + 1) * 4].copy_from_slice(&word.to_be_bytes());
+                }
+                *slot = None;
+            }
+        }
+    }
+    digests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256_transform_generic;
+
+    /// 汎用実装で8レーン分を順に処理する、テスト用の圧縮関数
+    fn transform8_generic(states: &mut [[u32; 8]; LANES], blocks: &[[u8; 64]; LANES]) {
+        for (state, block) in states.iter_mut().zip(blocks) {
+            let mut s = Sha256State { h: *state };
+            sha256_transform_generic(&mut s, block);
+            *state = s.h;
+        }
+    }
+
+    fn serial(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+        messages
+            .iter()
+            .map(|message| {
+                let mut hasher = Sha256::new();
+                hasher.update(message);
+                hasher.finalize()
+            })
+            .collect()
+    }
+
+    /// パディングの境界をまたぐ長さを含む、長さの異なるメッセージの集合
+    fn test_messages() -> Vec<Vec<u8>> {
+        let mut lengths = vec![0, 1, 3, 55, 56, 57, 63, 64, 65, 119, 120, 127, 128, 1000];
+        let mut seed = 0x1234_5678u32;
+        for _ in 0..37 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            lengths.push((seed >> 16) as usize % 300);
+        }
+        lengths
+            .iter()
+            .enumerate()
+            .map(|(n, &len)| (0..len).map(|i| (i * 7 + n) as u8).collect())
+            .collect()
+    }
+
+    // パディング済みブロックが Sha256 と同じ内部状態を与えること
+    #[test]
+    fn test_lane_padding() {
+        let messages = test_messages();
+        let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        assert_eq!(hash_lanes(&refs, transform8_generic), serial(&refs));
+    }
+
+    // hash_many（実行環境で選択される実装）が逐次計算と一致すること
+    #[test]
+    fn test_hash_many_matches_serial() {
+        let messages = test_messages();
+        for count in [0, 1, 7, 8, 9, messages.len()] {
+            let refs: Vec<&[u8]> = messages[..count].iter().map(Vec::as_slice).collect();
+            assert_eq!(hash_many(&refs), serial(&refs), "{} messages", count);
+        }
+    }
+
+    // "abc" の既知のハッシュ値
+    #[test]
+    fn test_hash_many_abc() {
+        let digests = hash_many(&[b"abc", b""]);
+        assert_eq!(digests[0][..4], [0xba, 0x78, 0x16, 0xbf]);
+        assert_eq!(digests[1][..4], [0xe3, 0xb0, 0xc4, 0x42]);
+    }
+
+    // AVX2実装を直接テストする（非対応CPUでは省略）
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_transform8_matches_generic() {
+        if !crate::avx2::is_supported() {
+            return;
+        }
+        let mut blocks = [[0u8; 64]; LANES];
+        let mut states = [[0u32; 8]; LANES];
+        for lane in 0..LANES {
+            blocks[lane] = std::array::from_fn(|i| (lane * 64 + i * 3) as u8);
+            states[lane] = std::array::from_fn(|i| ((lane * 8 + i) as u32).wrapping_mul(0x9e37_79b9));
+        }
+
+        let mut expected = states;
+        transform8_generic(&mut expected, &blocks);
+        // SAFETY: AVX2が利用できることを確認済み
+        unsafe { crate::avx2::transform8(&mut states, &blocks) };
+        assert_eq!(states, expected);
+    }
+
+    // 利用できるすべての実装が逐次計算と一致すること（AVX2はSHA-NIの有無によらず検証する）
+    #[test]
+    fn test_hash_many_with() {
+        let messages = test_messages();
+        let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        for backend in [ManyBackend::Serial, ManyBackend::Avx2] {
+            match hash_many_with(backend, &refs) {
+                Some(digests) => assert_eq!(digests, serial(&refs), "{}", backend.name()),
+                None => assert!(!backend.is_available(), "{}", backend.name()),
+            }
+        }
+        assert!(ManyBackend::best().is_available());
+    }
+}
@@ -0,0 +1,134 @@
+// PBKDF2-HMAC-SHA256（RFC 8018 §5.2）によるパスワードベース鍵導出
+
+use crate::HmacSha256;
+
+/// SHA-256の出力長（hLen）
+const HASH_LEN: usize = 32;
+
+/// PBKDF2の処理で発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pbkdf2Error {
+    /// 反復回数が0
+    ZeroRounds,
+    /// 要求された出力長が (2^32 - 1) * hLen を超えている
+    InvalidLength(usize),
+}
+
+impl std::fmt::Display for Pbkdf2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pbkdf2Error::ZeroRounds => write!(f, "PBKDF2: iteration count must be at least 1"),
+            Pbkdf2Error::InvalidLength(len) => {
+                write!(f, "PBKDF2: derived key length {} is too long", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Pbkdf2Error {}
+
+/// PBKDF2-HMAC-SHA256で `password` と `salt` から `output` 全体を埋める鍵を導出
+///
+/// 出力は32バイト単位のブロック T_1, T_2, ... を連結したもので、任意の長さを指定できます。
+/// パスワードで鍵付けしたHMACの内側・外側状態は一度だけ計算し、各反復で使い回します。
+pub fn pbkdf2(
+    password: &[u8],
+    salt: &[u8],
+    rounds: u32,
+    output: &mut [u8],
+) -> Result<(), Pbkdf2Error> {
+    if rounds == 0 {
+        return Err(Pbkdf2Error::ZeroRounds);
+    }
+    if output.len() as u64 > u32::MAX as u64 * HASH_LEN as u64 {
+        return Err(Pbkdf2Error::InvalidLength(output.len()));
+    }
+
+    let prf = HmacSha256::new(password);
+
+    for (i, chunk) in output.chunks_mut(HASH_LEN).enumerate() {
+        // U_1 = PRF(P, S || INT(i))
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize();
+        let mut t = u;
+
+        // U_j = PRF(P, U_{j-1}) を反復し、T_i = U_1 ^ U_2 ^ ... ^ U_c を求める
+        for _ in 1..rounds {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for (x, y) in t.iter_mut().zip(u.iter()) {
+                *x ^= y;
+            }
+        }
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn derive(password: &[u8], salt: &[u8], rounds: u32, len: usize) -> Vec<u8> {
+        let mut output = vec![0u8; len];
+        pbkdf2(password, salt, rounds, &mut output).unwrap();
+        output
+    }
+
+    // RFC 7914 §11 のPBKDF2-HMAC-SHA256テストベクトル（複数ブロックの出力）
+    #[test]
+    fn test_pbkdf2_sha256_rfc7914() {
+        assert_eq!(
+            derive(b"passwd", b"salt", 1, 64),
+            hex("55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+                 49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783")
+        );
+        assert_eq!(
+            derive(b"Password", b"NaCl", 80000, 64),
+            hex("4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+                 a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d")
+        );
+    }
+
+    // RFC 6070 と同じ入力に対するPBKDF2-HMAC-SHA256の値
+    #[test]
+    fn test_pbkdf2_sha256_rfc6070_inputs() {
+        assert_eq!(
+            derive(b"password", b"salt", 1, 32),
+            hex("120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b")
+        );
+        assert_eq!(
+            derive(b"password", b"salt", 2, 32),
+            hex("ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43")
+        );
+        assert_eq!(
+            derive(b"password", b"salt", 4096, 32),
+            hex("c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a")
+        );
+        // 出力長がブロック長の倍数でない場合
+        assert_eq!(
+            derive(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 40),
+            hex("348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9")
+        );
+        // NULバイトを含むパスワード・ソルト
+        assert_eq!(
+            derive(b"pass\0word", b"sa\0lt", 4096, 16),
+            hex("89b69d0516f829893c696226650a8687")
+        );
+    }
+
+    // 反復回数0はエラーになること
+    #[test]
+    fn test_pbkdf2_sha256_zero_rounds() {
+        let mut output = [0u8; 32];
+        assert_eq!(
+            pbkdf2(b"password", b"salt", 0, &mut output),
+            Err(Pbkdf2Error::ZeroRounds)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+// 途中状態のエクスポート／インポート（中断したハッシュ計算の再開用）
+//
+// バイト列のフォーマット（バージョン1、数値はすべてビッグエンディアン）:
+//
+//   オフセット  長さ  内容
+//   0           4     マジック "SHAS"
+//   4           1     フォーマットバージョン (1)
+//   5           1     アルゴリズムID (1 = SHA-256, 2 = SHA-224)
+//   6           32    連鎖値 H0〜H7（u32 × 8）
+//   38          8     これまでに入力した総バイト数（u64）
+//   46          1     バッファ内のバイト数 n（0〜63）
+//   47          n     未処理のバッファ内容
+//
+// アルゴリズムIDはsha512_armと共通の番号空間を使用しており、
+// SHA-512系の状態を誤って読み込んだ場合もエラーとして検出できます。
+
+use crate::{Sha224, Sha256, Sha256State};
+
+/// フォーマット識別用のマジックバイト
+const MAGIC: &[u8; 4] = b"SHAS";
+
+/// 現在のフォーマットバージョン
+const VERSION: u8 = 1;
+
+/// アルゴリズムID
+const ALG_SHA256: u8 = 1;
+const ALG_SHA224: u8 = 2;
+
+/// 固定長部分（バッファ内容を除く）のバイト数
+const HEADER_LEN: usize = 4 + 1 + 1 + 32 + 8 + 1;
+
+/// 途中状態のインポートで発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// マジックバイトが一致しない
+    InvalidMagic,
+    /// 対応していないフォーマットバージョン
+    UnsupportedVersion(u8),
+    /// 読み込み先と異なるアルゴリズムの状態
+    AlgorithmMismatch { expected: u8, found: u8 },
+    /// バイト列の長さがフォーマットと一致しない
+    InvalidLength(usize),
+    /// バッファ長と総バイト数の整合性が取れていない
+    Inconsistent,
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::InvalidMagic => write!(f, "hash state: invalid magic bytes"),
+            StateError::UnsupportedVersion(v) => {
+                write!(f, "hash state: unsupported format version {}", v)
+            }
+            StateError::AlgorithmMismatch { expected, found } => write!(
+                f,
+                "hash state: algorithm id {} does not match expected id {}",
+                found, expected
+            ),
+            StateError::InvalidLength(len) => write!(f, "hash state: invalid length {}", len),
+            StateError::Inconsistent => {
+                write!(f, "hash state: buffer length does not match total length")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+impl Sha256 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.export_with(ALG_SHA256)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Self::import_with(bytes, ALG_SHA256)
+    }
+
+    fn export_with(&self, algorithm: u8) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.buffer_len);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(algorithm);
+        for word in &self.state.h {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+        out.extend_from_slice(&self.total_len.to_be_bytes());
+        out.push(self.buffer_len as u8);
+        out.extend_from_slice(&self.buffer[..self.buffer_len]);
+        out
+    }
+
+    fn import_with(bytes: &[u8], algorithm: u8) -> Result<Self, StateError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(StateError::InvalidLength(bytes.len()));
+        }
+        if &bytes[0..4] != MAGIC {
+            return Err(StateError::InvalidMagic);
+        }
+        if bytes[4] != VERSION {
+            return Err(StateError::UnsupportedVersion(bytes[4]));
+        }
+        if bytes[5] != algorithm {
+            return Err(StateError::AlgorithmMismatch {
+                expected: algorithm,
+                found: bytes[5],
+            });
+        }
+
+        let buffer_len = bytes[46] as usize;
+        if buffer_len >= 64 {
+            return Err(StateError::Inconsistent);
+        }
+        if bytes.len() != HEADER_LEN + buffer_len {
+            return Err(StateError::InvalidLength(bytes.len()));
+        }
+
+        let mut state = Sha256State { h: [0; 8] };
+        for (i, word) in state.h.iter_mut().enumerate() {
+            let offset = 6 + i * 4;
+            *word = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        }
+        let total_len = u64::from_be_bytes(bytes[38..46].try_into().unwrap());
+
+        // バッファには常に「総バイト数 mod 64」バイトが残っているはず
+        if total_len % 64 != buffer_len as u64 {
+            return Err(StateError::Inconsistent);
+        }
+
+        let mut hasher = Sha256::with_state(state);
+        hasher.buffer[..buffer_len].copy_from_slice(&bytes[HEADER_LEN..]);
+        hasher.buffer_len = buffer_len;
+        hasher.total_len = total_len;
+        Ok(hasher)
+    }
+}
+
+impl Sha224 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.inner.export_with(ALG_SHA224)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Ok(Self {
+            inner: Sha256::import_with(bytes, ALG_SHA224)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 様々な位置で中断・再開しても、一括で計算した場合と同じハッシュ値になること
+    #[test]
+    fn test_sha256_resume_round_trip() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 3) as u8).collect();
+
+        let mut whole = Sha256::new();
+        whole.update(&data);
+        let expected = whole.finalize();
+
+        for split in [0, 1, 55, 63, 64, 65, 128, 500, 999, 1000] {
+            let mut first = Sha256::new();
+            first.update(&data[..split]);
+            let exported = first.export_state();
+
+            let mut resumed = Sha256::import_state(&exported).unwrap();
+            resumed.update(&data[split..]);
+            assert_eq!(resumed.finalize(), expected, "split = {}", split);
+        }
+    }
+
+    // SHA-224の途中状態も同様に再開できること
+    #[test]
+    fn test_sha224_resume_round_trip() {
+        let data = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+        let mut whole = Sha224::new();
+        whole.update(data);
+        let expected = whole.finalize();
+
+        let mut first = Sha224::new();
+        first.update(&data[..20]);
+        let mut resumed = Sha224::import_state(&first.export_state()).unwrap();
+        resumed.update(&data[20..]);
+        assert_eq!(resumed.finalize(), expected);
+    }
+
+    // 不正なバイト列は型付きエラーとして拒否されること
+    #[test]
+    fn test_import_state_validation() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"hello world");
+        let exported = hasher.export_state();
+
+        // 異なるアルゴリズムとして読み込もうとした場合
+        assert_eq!(
+            Sha224::import_state(&exported).err(),
+            Some(StateError::AlgorithmMismatch { expected: ALG_SHA224, found: ALG_SHA256 })
+        );
+
+        let mut bad_magic = exported.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(Sha256::import_state(&bad_magic).err(), Some(StateError::InvalidMagic));
+
+        let mut bad_version = exported.clone();
+        bad_version[4] = 2;
+        assert_eq!(
+            Sha256::import_state(&bad_version).err(),
+            Some(StateError::UnsupportedVersion(2))
+        );
+
+        let truncated = &exported[..exported.len() - 1];
+        assert_eq!(
+            Sha256::import_state(truncated).err(),
+            Some(StateError::InvalidLength(truncated.len()))
+        );
+
+        // 総バイト数とバッファ長の不整合
+        let mut inconsistent = exported.clone();
+        inconsistent[45] ^= 1;
+        assert_eq!(
+            Sha256::import_state(&inconsistent).err(),
+            Some(StateError::Inconsistent)
+        );
+    }
+}
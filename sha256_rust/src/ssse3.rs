@@ -0,0 +1,189 @@
+// SSSE3 / AVX によるメッセージスケジュールのベクトル化（SHA-NIを持たないx86向け）
+//
+// Intel の "sha256-avx" 実装と同じ方針で、W[16..63] を128ビットレジスタで4ワードずつ計算し、
+// W[t] + K[t] を求めます。ラウンド処理はスカラーで、unrolled バックエンドと同じ展開済み・
+// レジスタリネーミングの形で行い、次の8ワード分のスケジュール計算をラウンドと並行して実行させます。
+// 同じコードを SSSE3 と AVX（VEX符号化）の2通りでコンパイルし、実行時に使える方を選択します。
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::backend::BlockCompressor;
+use crate::unrolled::round;
+use crate::{Sha256State, K};
+
+/// 4レーンの32ビット右ローテート
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm_or_si128(_mm_srli_epi32::<$n>($x), _mm_slli_epi32::<{ 32 - $n }>($x))
+    };
+}
+
+/// 4ワード前までの W（x0 = W[t-16..t-13] 〜 x3 = W[t-4..t-1]）から W[t..t+4] を計算する
+macro_rules! schedule {
+    ($x0:expr, $x1:expr, $x2:expr, $x3:expr) => {{
+        let (x0, x1, x2, x3) = ($x0, $x1, $x2, $x3);
+        // W[t-15..t-12] と W[t-7..t-4]
+        let w15 = _mm_alignr_epi8::<4>(x1, x0);
+        let w7 = _mm_alignr_epi8::<4>(x3, x2);
+        let s0 = _mm_xor_si128(
+            _mm_xor_si128(rotr!(w15, 7), rotr!(w15, 18)),
+            _mm_srli_epi32::<3>(w15),
+        );
+        let t = _mm_add_epi32(_mm_add_epi32(x0, s0), w7);
+
+        // σ1 は2ワード前の値に依存するため、下位2ワードと上位2ワードに分けて加算する
+        let lo = _mm_shuffle_epi32::<0xee>(x3);   // W[t-2], W[t-1]
+        let s1 = _mm_xor_si128(
+            _mm_xor_si128(rotr!(lo, 17), rotr!(lo, 19)),
+            _mm_srli_epi32::<10>(lo),
+        );
+        let t = _mm_add_epi32(t, _mm_move_epi64(s1));
+
+        let hi = _mm_shuffle_epi32::<0x44>(t);    // W[t], W[t+1]
+        let s1 = _mm_xor_si128(
+            _mm_xor_si128(rotr!(hi, 17), rotr!(hi, 19)),
+            _mm_srli_epi32::<10>(hi),
+        );
+        _mm_add_epi32(t, _mm_slli_si128::<8>(s1))
+    }};
+}
+
+/// 有効にする命令セット拡張ごとに圧縮関数を生成する
+macro_rules! compress_fn {
+    ($name:ident, $feature:literal) => {
+        /// SIMDでW+Kを求め、展開済みのスカラーのラウンドで処理する圧縮関数
+        #[target_feature(enable = $feature)]
+        unsafe fn $name(state: &mut Sha256State, blocks: &[[u8; 64]]) {
+            // 各32ビットワードをビッグエンディアンとして読み込むためのバイト並べ替えマスク
+            let mask = _mm_set_epi64x(
+                0x0c0d_0e0f_0809_0a0b_u64 as i64,
+                0x0405_0607_0001_0203_u64 as i64,
+            );
+
+            for block in blocks {
+                // 直近の16ワードの W（4ワードずつ、W[t] は w[(t / 4) % 4] に入る）
+                let data_ptr = block.as_ptr() as *const __m128i;
+                let mut w = [
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(1)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(2)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(3)), mask),
+                ];
+                let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.h;
+
+                // W[t..t+4] + K[t..t+4] を求めてスカラーで取り出す
+                macro_rules! wk {
+                    ($t:expr) => {{
+                        let k = _mm_loadu_si128(K.as_ptr().add($t) as *const __m128i);
+                        let mut wk = [0u32; 4];
+                        _mm_storeu_si128(wk.as_mut_ptr() as *mut __m128i, _mm_add_epi32(w[($t / 4) % 4], k));
+                        wk
+                    }};
+                }
+
+                // 8ラウンド分（作業変数の並びが元に戻る）
+                macro_rules! round8 {
+                    ($wk0:ident, $wk1:ident) => {
+                        round!(a, b, c, d, e, f, g, h, $wk0[0]);
+                        round!(h, a, b, c, d, e, f, g, $wk0[1]);
+                        round!(g, h, a, b, c, d, e, f, $wk0[2]);
+                        round!(f, g, h, a, b, c, d, e, $wk0[3]);
+                        round!(e, f, g, h, a, b, c, d, $wk1[0]);
+                        round!(d, e, f, g, h, a, b, c, $wk1[1]);
+                        round!(c, d, e, f, g, h, a, b, $wk1[2]);
+                        round!(b, c, d, e, f, g, h, a, $wk1[3]);
+                    };
+                }
+
+                // ラウンド t〜t+7（schedule を指定した場合は、使い終えた W[t..t+8] の位置に
+                // W[t+16..t+24] を計算し、ラウンドと並行して実行させる）
+                macro_rules! rounds8 {
+                    ($t:literal) => {
+                        let (wk0, wk1) = (wk!($t), wk!($t + 4));
+                        round8!(wk0, wk1);
+                    };
+                    ($t:literal, schedule) => {
+                        let (wk0, wk1) = (wk!($t), wk!($t + 4));
+                        let i = ($t / 4) % 4;
+                        w[i] = schedule!(w[i], w[i + 1], w[(i + 2) % 4], w[(i + 3) % 4]);
+                        w[i + 1] = schedule!(w[i + 1], w[(i + 2) % 4], w[(i + 3) % 4], w[i]);
+                        round8!(wk0, wk1);
+                    };
+                }
+
+                rounds8!(0, schedule);
+                rounds8!(8, schedule);
+                rounds8!(16, schedule);
+                rounds8!(24, schedule);
+                rounds8!(32, schedule);
+                rounds8!(40, schedule);
+                rounds8!(48);
+                rounds8!(56);
+
+                for (s, v) in state.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                    *s = s.wrapping_add(v);
+                }
+            }
+        }
+    };
+}
+
+compress_fn!(compress_ssse3, "ssse3");
+compress_fn!(compress_avx, "avx");
+
+/// SSSE3でメッセージスケジュールを計算するバックエンド
+pub struct Ssse3;
+
+impl BlockCompressor for Ssse3 {
+    fn name(&self) -> &'static str {
+        "x86-ssse3"
+    }
+
+    fn is_available(&self) -> bool {
+        is_x86_feature_detected!("ssse3")
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        assert!(self.is_available(), "x86-ssse3: CPU does not support SSSE3");
+        // SAFETY: SSSE3が利用できることを直前に確認している
+        unsafe { compress_ssse3(state, blocks) }
+    }
+}
+
+/// AVX（VEX符号化）でメッセージスケジュールを計算するバックエンド
+pub struct Avx;
+
+impl BlockCompressor for Avx {
+    fn name(&self) -> &'static str {
+        "x86-avx"
+    }
+
+    fn is_available(&self) -> bool {
+        is_x86_feature_detected!("avx")
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        assert!(self.is_available(), "x86-avx: CPU does not support AVX");
+        // SAFETY: AVXが利用できることを直前に確認している（AVXはSSSE3を含む）
+        unsafe { compress_avx(state, blocks) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_ssse3() {
+        check_backend(&Ssse3);
+    }
+
+    #[test]
+    fn test_avx() {
+        check_backend(&Avx);
+    }
+}
@@ -0,0 +1,156 @@
+// x86 / x86-64 SHA拡張命令（SHA-NI）による圧縮関数
+//
+// SHA256RNDS2 で2ラウンドずつ、SHA256MSG1 / SHA256MSG2 でメッセージスケジュールを計算します。
+// SHA-NI は状態を ABEF / CDGH の2本のレジスタで保持するため、
+// ブロック列の前後で標準の H0〜H7 の並びとの並べ替えを行います。
+// 一部のCPUにしか実装されていないため、`is_x86_feature_detected!("sha")` で実行時に確認します。
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::backend::BlockCompressor;
+use crate::{Sha256State, K};
+
+/// 実行中のCPUがSHA-NI（および併用するSSE4.1までの命令）に対応しているかどうか
+pub fn is_supported() -> bool {
+    is_x86_feature_detected!("sha")
+        && is_x86_feature_detected!("sse2")
+        && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1")
+}
+
+/// 外部公開用の圧縮関数インターフェース
+/// SHA-NIに対応していないCPUで呼び出した場合はパニックします。
+pub fn compress256(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    assert!(is_supported(), "compress256: CPU does not support the SHA extensions");
+    // SAFETY: 必要な命令セット拡張が利用できることを直前に確認している
+    unsafe { sha256_compress(state, blocks) }
+}
+
+/// 次の4ワード分のメッセージスケジュール W[t..t+4] を計算する
+/// v0〜v3 はそれぞれ W[t-16..t-12]、W[t-12..t-8]、W[t-8..t-4]、W[t-4..t] です。
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3")]
+unsafe fn schedule(v0: __m128i, v1: __m128i, v2: __m128i, v3: __m128i) -> __m128i {
+    // σ0 の加算（SHA256MSG1）と、W[t-7..t-3] の加算
+    let t1 = _mm_sha256msg1_epu32(v0, v1);
+    let t2 = _mm_alignr_epi8(v3, v2, 4);
+    let t3 = _mm_add_epi32(t1, t2);
+    // σ1 の加算（SHA256MSG2）
+    _mm_sha256msg2_epu32(t3, v3)
+}
+
+/// SHA-NIを使用したメインの圧縮ロジック
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn sha256_compress(state: &mut [u32; 8], blocks: &[[u8; 64]]) {
+    // 各32ビットワードをビッグエンディアンとして読み込むためのバイト並べ替えマスク
+    let mask = _mm_set_epi64x(
+        0x0c0d_0e0f_0809_0a0b_u64 as i64,
+        0x0405_0607_0001_0203_u64 as i64,
+    );
+
+    // H0〜H7 を SHA-NI が要求する ABEF / CDGH の並びに変換
+    let state_ptr = state.as_ptr() as *const __m128i;
+    let dcba = _mm_loadu_si128(state_ptr);
+    let efgh = _mm_loadu_si128(state_ptr.add(1));
+    let cdab = _mm_shuffle_epi32(dcba, 0xb1);
+    let efgh = _mm_shuffle_epi32(efgh, 0x1b);
+    let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xf0);
+
+    for block in blocks {
+        // 各ブロック処理の最後に元の状態を加算するため、初期値を保存
+        let abef_orig = abef;
+        let cdgh_orig = cdgh;
+
+        // メッセージブロックをロードし、ビッグエンディアンからCPUのネイティブ形式へ変換
+        let data_ptr = block.as_ptr() as *const __m128i;
+        let mut w0 = _mm_shuffle_epi8(_mm_loadu_si128(data_ptr), mask);
+        let mut w1 = _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(1)), mask);
+        let mut w2 = _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(2)), mask);
+        let mut w3 = _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(3)), mask);
+        let mut w4;
+
+        // 4ラウンド分の計算（SHA256RNDS2 を2回）を行うマクロ
+        macro_rules! rounds4 {
+            ($w:expr, $i:expr) => {{
+                // メッセージスケジュール(W)と定数(K)を事前に加算
+                let wk = _mm_add_epi32($w, _mm_loadu_si128(K.as_ptr().add($i * 4) as *const __m128i));
+                cdgh = _mm_sha256rnds2_epu32(cdgh, abef, wk);
+                // 上位2ワードを下位へ移して後半の2ラウンドを実行
+                abef = _mm_sha256rnds2_epu32(abef, cdgh, _mm_shuffle_epi32(wk, 0x0e));
+            }};
+        }
+
+        // メッセージを拡張しつつ4ラウンド分を計算するマクロ
+        macro_rules! schedule_rounds4 {
+            ($w0:expr, $w1:expr, $w2:expr, $w3:expr, $w4:expr, $i:expr) => {{
+                $w4 = schedule($w0, $w1, $w2, $w3);
+                rounds4!($w4, $i);
+            }};
+        }
+
+        // 最初の16ラウンド（入力メッセージをそのまま使用）
+        rounds4!(w0, 0);
+        rounds4!(w1, 1);
+        rounds4!(w2, 2);
+        rounds4!(w3, 3);
+
+        // 残りの48ラウンド（5本のレジスタを循環させてメッセージを拡張）
+        schedule_rounds4!(w0, w1, w2, w3, w4, 4);
+        schedule_rounds4!(w1, w2, w3, w4, w0, 5);
+        schedule_rounds4!(w2, w3, w4, w0, w1, 6);
+        schedule_rounds4!(w3, w4, w0, w1, w2, 7);
+        schedule_rounds4!(w4, w0, w1, w2, w3, 8);
+        schedule_rounds4!(w0, w1, w2, w3, w4, 9);
+        schedule_rounds4!(w1, w2, w3, w4, w0, 10);
+        schedule_rounds4!(w2, w3, w4, w0, w1, 11);
+        schedule_rounds4!(w3, w4, w0, w1, w2, 12);
+        schedule_rounds4!(w4, w0, w1, w2, w3, 13);
+        schedule_rounds4!(w0, w1, w2, w3, w4, 14);
+        schedule_rounds4!(w1, w2, w3, w4, w0, 15);
+
+        // ブロック処理後の状態に、処理前の状態を加算（SHA-256の仕様）
+        abef = _mm_add_epi32(abef, abef_orig);
+        cdgh = _mm_add_epi32(cdgh, cdgh_orig);
+    }
+
+    // ABEF / CDGH の並びを H0〜H7 に戻してメモリへ書き戻す
+    let feba = _mm_shuffle_epi32(abef, 0x1b);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xb1);
+    let dcba = _mm_blend_epi16(feba, dchg, 0xf0);
+    let hgef = _mm_alignr_epi8(dchg, feba, 8);
+    let state_ptr = state.as_mut_ptr() as *mut __m128i;
+    _mm_storeu_si128(state_ptr, dcba);
+    _mm_storeu_si128(state_ptr.add(1), hgef);
+}
+
+/// SHA-NIを使用するバックエンド
+pub struct ShaNi;
+
+impl BlockCompressor for ShaNi {
+    fn name(&self) -> &'static str {
+        "x86-sha-ni"
+    }
+
+    fn is_available(&self) -> bool {
+        is_supported()
+    }
+
+    fn compress(&self, state: &mut Sha256State, blocks: &[[u8; 64]]) {
+        compress256(&mut state.h, blocks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_sha_ni() {
+        check_backend(&ShaNi);
+    }
+}
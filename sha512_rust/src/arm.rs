@@ -0,0 +1,260 @@
+// 32ビットARM（ARMv7 / AArch32）向けのNEONインラインアセンブリによる圧縮関数
+//
+// libgcrypt の sha512-armv7-neon.S と同じく、64ビットの作業変数 a〜h を d0〜d7 に置き、
+// 64ビット単位のシフト（VSHR / VSLI）でローテートを行います。
+// メッセージスケジュール W[t] + K[t] は128ビットレジスタで2ワードずつ事前に計算します。
+// AArch32 の暗号拡張にはSHA-512命令がないため、NEONに対応したCPUでのみ使用します。
+//
+// 32ビットARMでは `is_arm_feature_detected!` や `#[target_feature(enable = "neon")]` が安定版で使えないため、
+// NEONの対応状況は /proc/self/auxv の HWCAP から判定し、NEON命令はアセンブラの `.fpu` 指定で
+// 一時的に有効にしています（ブロックの最後で armv7-unknown-linux-gnueabihf の既定である VFPv3-D16 に戻します）。
+// VFPv3-D16 のCPUでも組み込めるよう、使用するSIMDレジスタは q0〜q7（d0〜d15）に限っています。
+
+use core::arch::asm;
+use std::sync::OnceLock;
+
+use crate::backend::BlockCompressor;
+use crate::{Sha512State, K};
+
+/// HWCAP の NEON ビット（Linux の asm/hwcap.h）
+const HWCAP_NEON: u32 = 1 << 12;
+
+/// カーネルから渡される HWCAP の値（プロセス内でキャッシュされる）
+fn hwcap() -> u32 {
+    static HWCAP: OnceLock<u32> = OnceLock::new();
+    *HWCAP.get_or_init(read_hwcap)
+}
+
+/// 補助ベクタ（/proc/self/auxv）から HWCAP を読み取る
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn read_hwcap() -> u32 {
+    const AT_HWCAP: u32 = 16;
+
+    let Ok(auxv) = std::fs::read("/proc/self/auxv") else {
+        return 0;
+    };
+    // 32ビット環境では (種別, 値) の32ビットワードの組が並んでいる
+    auxv.chunks_exact(8)
+        .find(|entry| u32::from_ne_bytes(entry[..4].try_into().unwrap()) == AT_HWCAP)
+        .map_or(0, |entry| u32::from_ne_bytes(entry[4..].try_into().unwrap()))
+}
+
+/// 補助ベクタを読めない環境では、NEONを非対応として扱う
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn read_hwcap() -> u32 {
+    0
+}
+
+/// 実行中のCPUがNEON（Advanced SIMD）に対応しているかどうか
+pub fn is_supported() -> bool {
+    hwcap() & HWCAP_NEON != 0
+}
+
+/// 外部公開用の圧縮関数インターフェース
+/// NEONに対応していないCPUで呼び出した場合はパニックします。
+pub fn compress512(state: &mut [u64; 8], blocks: &[[u8; 128]]) {
+    assert!(is_supported(), "compress512: CPU does not support NEON");
+    let mut wk = [0u64; 80];
+    for block in blocks {
+        // SAFETY: NEONが利用できることを直前に確認している
+        unsafe {
+            schedule(&mut wk, block);
+            rounds(state, &wk);
+        }
+    }
+}
+
+/// メッセージスケジュール W[0..79] を計算し、K[t] を加算した値を wk に格納する
+unsafe fn schedule(wk: &mut [u64; 80], block: &[u8; 128]) {
+    asm!(
+        ".fpu neon",
+        // W[0..15]: 2ワードずつ読み込み、ワードごとにバイト順を反転する
+        "vld1.8 {{d0-d3}}, [{data}]!",
+        "vld1.8 {{d4-d7}}, [{data}]!",
+        "vld1.8 {{d8-d11}}, [{data}]!",
+        "vld1.8 {{d12-d15}}, [{data}]",
+        "vrev64.8 q0, q0",
+        "vrev64.8 q1, q1",
+        "vrev64.8 q2, q2",
+        "vrev64.8 q3, q3",
+        "vrev64.8 q4, q4",
+        "vrev64.8 q5, q5",
+        "vrev64.8 q6, q6",
+        "vrev64.8 q7, q7",
+        "vst1.64 {{d0-d3}}, [{w}]!",
+        "vst1.64 {{d4-d7}}, [{w}]!",
+        "vst1.64 {{d8-d11}}, [{w}]!",
+        "vst1.64 {{d12-d15}}, [{w}]!",
+        // W[t], W[t+1] を2ワードずつ計算する
+        // σ1 が参照する W[t-2], W[t-1] は直前に計算した2ワードなので、レーン間の依存はない
+        "mov {n}, #32",
+        "2:",
+        "sub {p}, {w}, #128",
+        "vld1.64 {{d0-d3}}, [{p}]",     // W[t-16..t-13]
+        "add {p}, {p}, #64",
+        "vld1.64 {{d4-d7}}, [{p}]",     // W[t-8..t-5]
+        "add {p}, {p}, #48",
+        "vld1.64 {{d12, d13}}, [{p}]",  // W[t-2..t-1]
+        "vext.8 q4, q0, q1, #8",        // W[t-15..t-14]
+        "vext.8 q5, q2, q3, #8",        // W[t-7..t-6]
+        "vadd.i64 q0, q0, q5",
+        // σ0(x) = ROTR1(x) ^ ROTR8(x) ^ SHR7(x)
+        "vshr.u64 q1, q4, #1",
+        "vsli.64 q1, q4, #63",
+        "vshr.u64 q2, q4, #8",
+        "vsli.64 q2, q4, #56",
+        "veor q1, q1, q2",
+        "vshr.u64 q2, q4, #7",
+        "veor q1, q1, q2",
+        "vadd.i64 q0, q0, q1",
+        // σ1(x) = ROTR19(x) ^ ROTR61(x) ^ SHR6(x)
+        "vshr.u64 q1, q6, #19",
+        "vsli.64 q1, q6, #45",
+        "vshr.u64 q2, q6, #61",
+        "vsli.64 q2, q6, #3",
+        "veor q1, q1, q2",
+        "vshr.u64 q2, q6, #6",
+        "veor q1, q1, q2",
+        "vadd.i64 q0, q0, q1",
+        "vst1.64 {{d0, d1}}, [{w}]!",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        // W[t] + K[t] で置き換える
+        "sub {w}, {w}, #640",
+        "mov {n}, #40",
+        "2:",
+        "vld1.64 {{d0, d1}}, [{w}]",
+        "vld1.64 {{d2, d3}}, [{k}]!",
+        "vadd.i64 q0, q0, q1",
+        "vst1.64 {{d0, d1}}, [{w}]!",
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        ".fpu vfpv3-d16",
+        w = inout(reg) wk.as_mut_ptr() => _,
+        data = inout(reg) block.as_ptr() => _,
+        k = inout(reg) K.as_ptr() => _,
+        n = out(reg) _,
+        p = out(reg) _,
+        out("d0") _, out("d1") _, out("d2") _, out("d3") _,
+        out("d4") _, out("d5") _, out("d6") _, out("d7") _,
+        out("d8") _, out("d9") _, out("d10") _, out("d11") _,
+        out("d12") _, out("d13") _, out("d14") _, out("d15") _,
+        options(nostack),
+    );
+}
+
+/// 1ラウンド分の命令列（d8〜d11 は一時レジスタ）
+/// h に T1 + T2 を求めて次の a とし、d に T1 を加算して次の e とします。
+macro_rules! round {
+    ($a:literal, $b:literal, $c:literal, $d:literal,
+     $e:literal, $f:literal, $g:literal, $h:literal) => {
+        concat!(
+            // Σ1(e) = ROTR14(e) ^ ROTR18(e) ^ ROTR41(e)
+            "vshr.u64 d8, ", $e, ", #14\n",
+            "vsli.64 d8, ", $e, ", #50\n",
+            "vshr.u64 d9, ", $e, ", #18\n",
+            "vsli.64 d9, ", $e, ", #46\n",
+            "veor d8, d8, d9\n",
+            "vshr.u64 d9, ", $e, ", #41\n",
+            "vsli.64 d9, ", $e, ", #23\n",
+            "veor d8, d8, d9\n",
+            // Ch(e, f, g): e の各ビットで f と g を選択する
+            "vmov d10, ", $e, "\n",
+            "vbsl d10, ", $f, ", ", $g, "\n",
+            // h += Σ1(e) + Ch(e, f, g) + W[t] + K[t]
+            "vld1.64 {{d11}}, [{wk}]!\n",
+            "vadd.i64 ", $h, ", ", $h, ", d8\n",
+            "vadd.i64 d10, d10, d11\n",
+            "vadd.i64 ", $h, ", ", $h, ", d10\n",
+            // d += T1
+            "vadd.i64 ", $d, ", ", $d, ", ", $h, "\n",
+            // Σ0(a) = ROTR28(a) ^ ROTR34(a) ^ ROTR39(a)
+            "vshr.u64 d8, ", $a, ", #28\n",
+            "vsli.64 d8, ", $a, ", #36\n",
+            "vshr.u64 d9, ", $a, ", #34\n",
+            "vsli.64 d9, ", $a, ", #30\n",
+            "veor d8, d8, d9\n",
+            "vshr.u64 d9, ", $a, ", #39\n",
+            "vsli.64 d9, ", $a, ", #25\n",
+            "veor d8, d8, d9\n",
+            // Maj(a, b, c): a と b が異なるビットでは c、等しいビットでは b を選択する
+            "veor d10, ", $a, ", ", $b, "\n",
+            "vbsl d10, ", $c, ", ", $b, "\n",
+            // h += Σ0(a) + Maj(a, b, c)
+            "vadd.i64 ", $h, ", ", $h, ", d8\n",
+            "vadd.i64 ", $h, ", ", $h, ", d10\n",
+        )
+    };
+}
+
+/// W[t] + K[t] を使って80ラウンドを処理し、処理前の状態に加算する
+/// ラウンドごとに a〜h を割り当てるレジスタをずらし、8ラウンドを1回分としてループします。
+unsafe fn rounds(state: &mut [u64; 8], wk: &[u64; 80]) {
+    asm!(
+        ".fpu neon",
+        "vld1.64 {{d0-d3}}, [{state}]!",
+        "vld1.64 {{d4-d7}}, [{state}]",
+        "mov {n}, #10",
+        "2:",
+        round!("d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7"),
+        round!("d7", "d0", "d1", "d2", "d3", "d4", "d5", "d6"),
+        round!("d6", "d7", "d0", "d1", "d2", "d3", "d4", "d5"),
+        round!("d5", "d6", "d7", "d0", "d1", "d2", "d3", "d4"),
+        round!("d4", "d5", "d6", "d7", "d0", "d1", "d2", "d3"),
+        round!("d3", "d4", "d5", "d6", "d7", "d0", "d1", "d2"),
+        round!("d2", "d3", "d4", "d5", "d6", "d7", "d0", "d1"),
+        round!("d1", "d2", "d3", "d4", "d5", "d6", "d7", "d0"),
+        "subs {n}, {n}, #1",
+        "bne 2b",
+        // メモリ上の処理前の状態を加算して書き戻す
+        "sub {state}, {state}, #32",
+        "vld1.64 {{d8-d11}}, [{state}]!",
+        "vld1.64 {{d12-d15}}, [{state}]",
+        "sub {state}, {state}, #32",
+        "vadd.i64 q0, q0, q4",
+        "vadd.i64 q1, q1, q5",
+        "vadd.i64 q2, q2, q6",
+        "vadd.i64 q3, q3, q7",
+        "vst1.64 {{d0-d3}}, [{state}]!",
+        "vst1.64 {{d4-d7}}, [{state}]",
+        ".fpu vfpv3-d16",
+        state = inout(reg) state.as_mut_ptr() => _,
+        wk = inout(reg) wk.as_ptr() => _,
+        n = out(reg) _,
+        out("d0") _, out("d1") _, out("d2") _, out("d3") _,
+        out("d4") _, out("d5") _, out("d6") _, out("d7") _,
+        out("d8") _, out("d9") _, out("d10") _, out("d11") _,
+        out("d12") _, out("d13") _, out("d14") _, out("d15") _,
+        options(nostack),
+    );
+}
+
+/// NEONを使用するバックエンド
+pub struct ArmNeon;
+
+impl BlockCompressor for ArmNeon {
+    fn name(&self) -> &'static str {
+        "arm-neon"
+    }
+
+    fn is_available(&self) -> bool {
+        is_supported()
+    }
+
+    fn compress(&self, state: &mut Sha512State, blocks: &[[u8; 128]]) {
+        compress512(&mut state.h, blocks);
+    }
+}
+
+// qemu-user 上で実行できる（例: cargo test --target armv7-unknown-linux-gnueabihf、
+// ランナーは .cargo/config.toml を参照）。NEONに対応していない場合は省略する
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_arm_neon() {
+        check_backend(&ArmNeon);
+    }
+}
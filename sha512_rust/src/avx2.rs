@@ -0,0 +1,93 @@
+// AVX2による4レーン並列のSHA-512圧縮関数（マルチバッファ方式）
+//
+// 256ビットレジスタの各64ビットレーンに別々のメッセージの状態を割り当て、
+// 4本の独立したブロックを1回の呼び出しで同時に圧縮します。
+// 1本のメッセージを速くするものではなく、多数の短いメッセージのスループットを上げるための実装です。
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::many::LANES;
+use crate::K;
+
+/// 実行中のCPUがAVX2に対応しているかどうか
+pub(crate) fn is_supported() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+/// 4レーンの64ビット右ローテート
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm256_or_si256(_mm256_srli_epi64::<$n>($x), _mm256_slli_epi64::<{ 64 - $n }>($x))
+    };
+}
+
+/// 4本のブロックをそれぞれ対応する状態に対して圧縮する
+///
+/// # Safety
+/// 実行中のCPUがAVX2に対応している必要があります。
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn transform4(states: &mut [[u64; 8]; LANES], blocks: &[[u8; 128]; LANES]) {
+    // 各ブロックのt番目のワードを4レーンに並べてメッセージスケジュールW[0..15]を作成
+    let mut w = [_mm256_setzero_si256(); 80];
+    for (t, wt) in w.iter_mut().take(16).enumerate() {
+        let word = |lane: usize| {
+            u64::from_be_bytes(blocks[lane][t * 8..t * 8 + 8].try_into().unwrap()) as i64
+        };
+        *wt = _mm256_setr_epi64x(word(0), word(1), word(2), word(3));
+    }
+
+    // メッセージスケジュールをW[16..79]まで拡張（σ0, σ1）
+    for t in 16..80 {
+        let x = w[t - 15];
+        let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(x, 1), rotr!(x, 8)), _mm256_srli_epi64::<7>(x));
+        let y = w[t - 2];
+        let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(y, 19), rotr!(y, 61)), _mm256_srli_epi64::<6>(y));
+        w[t] = _mm256_add_epi64(_mm256_add_epi64(w[t - 16], s0), _mm256_add_epi64(w[t - 7], s1));
+    }
+
+    // 各レーンの状態を「ワードごと」のレジスタへ転置して読み込む
+    let mut v = [_mm256_setzero_si256(); 8];
+    for (i, vi) in v.iter_mut().enumerate() {
+        let h = |lane: usize| states[lane][i] as i64;
+        *vi = _mm256_setr_epi64x(h(0), h(1), h(2), h(3));
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = v;
+
+    // メインループ - 80ラウンドの圧縮処理を4レーン同時に実行
+    for t in 0..80 {
+        let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(e, 14), rotr!(e, 18)), rotr!(e, 41));
+        let ch = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+        let k = _mm256_set1_epi64x(K[t] as i64);
+        let temp1 = _mm256_add_epi64(
+            _mm256_add_epi64(_mm256_add_epi64(h, s1), _mm256_add_epi64(ch, k)),
+            w[t],
+        );
+        let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(a, 28), rotr!(a, 34)), rotr!(a, 39));
+        let maj = _mm256_xor_si256(
+            _mm256_xor_si256(_mm256_and_si256(a, b), _mm256_and_si256(a, c)),
+            _mm256_and_si256(b, c),
+        );
+        let temp2 = _mm256_add_epi64(s0, maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = _mm256_add_epi64(d, temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = _mm256_add_epi64(temp1, temp2);
+    }
+
+    // 処理前の状態に加算し、各レーンの状態へ書き戻す
+    for (i, x) in [a, b, c, d, e, f, g, h].into_iter().enumerate() {
+        let mut words = [0u64; LANES];
+        _mm256_storeu_si256(words.as_mut_ptr() as *mut __m256i, _mm256_add_epi64(x, v[i]));
+        for (lane, &word) in words.iter().enumerate() {
+            states[lane][i] = word;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// sha512sum互換のファイルハッシュ計算コマンド
+// GNU coreutils の sha512sum と同じ形式（テキスト・バイナリ・BSDタグ形式・NUL区切り）で出力し、
+// --check でチェックサム一覧を検証します。
+// 引数解析・出力・検証の処理は checksum_cli クレートで sha256sum と sha512sum に共通化しています。
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use checksum_cli::{read_chunks, ChecksumAlgorithm};
+use sha512_arm::{Sha384, Sha512, Sha512_224, Sha512_256};
+
+/// 検証モードで扱うアルゴリズム（出力モードでは常にSHA-512）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Sha384,
+    Sha512,
+    Sha512_224,
+    Sha512_256,
+}
+
+impl ChecksumAlgorithm for Algorithm {
+    const DEFAULT: Algorithm = Algorithm::Sha512;
+    const ALL: &'static [Algorithm] = &[
+        Algorithm::Sha384,
+        Algorithm::Sha512,
+        Algorithm::Sha512_224,
+        Algorithm::Sha512_256,
+    ];
+
+    /// BSD形式（--tag）で使用するアルゴリズム名（SHA-512/t はBSD系ツールの表記に合わせる）
+    fn tag(self) -> &'static str {
+        match self {
+            Algorithm::Sha384 => "SHA384",
+            Algorithm::Sha512 => "SHA512",
+            Algorithm::Sha512_224 => "SHA512t224",
+            Algorithm::Sha512_256 => "SHA512t256",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            Algorithm::Sha384 => 96,
+            Algorithm::Sha512 => 128,
+            Algorithm::Sha512_224 => 56,
+            Algorithm::Sha512_256 => 64,
+        }
+    }
+
+    /// 16進表記の長さからアルゴリズムを判別する
+    /// 56桁・64桁はSHA-224/SHA-256の出力と区別できないため、SHA-512/t はBSD形式のタグでのみ判別します。
+    fn from_hex_len(len: usize) -> Option<Self> {
+        [Algorithm::Sha384, Algorithm::Sha512]
+            .into_iter()
+            .find(|algorithm| algorithm.hex_len() == len)
+    }
+
+    fn digest<R: Read>(self, reader: R) -> io::Result<Vec<u8>> {
+        match self {
+            Algorithm::Sha384 => {
+                let mut hasher = Sha384::new();
+                read_chunks(reader, |chunk| hasher.update(chunk))?;
+                Ok(hasher.finalize().to_vec())
+            }
+            Algorithm::Sha512 => digest_reader(reader).map(|digest| digest.to_vec()),
+            Algorithm::Sha512_224 => {
+                let mut hasher = Sha512_224::new();
+                read_chunks(reader, |chunk| hasher.update(chunk))?;
+                Ok(hasher.finalize().to_vec())
+            }
+            Algorithm::Sha512_256 => {
+                let mut hasher = Sha512_256::new();
+                read_chunks(reader, |chunk| hasher.update(chunk))?;
+                Ok(hasher.finalize().to_vec())
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    checksum_cli::run::<Algorithm>(
+        "sha512sum",
+        concat!("sha512sum (sha512_arm) ", env!("CARGO_PKG_VERSION")),
+        print_help,
+    )
+}
+
+/// リーダーから終端まで読み込み、SHA-512を計算する
+fn digest_reader<R: Read>(mut reader: R) -> io::Result<[u8; 64]> {
+    let mut hasher = Sha512::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+fn print_help(prog: &str) {
+    println!("Usage: {} [OPTION]... [FILE]...", prog);
+    println!("Print SHA512 (512-bit) checksums.");
+    println!();
+    println!("With no FILE, or when FILE is -, read standard input.");
+    println!("  -b, --binary          read in binary mode");
+    println!("  -c, --check           read checksums from the FILEs and check them");
+    println!("      --tag             create a BSD-style checksum");
+    println!("  -t, --text            read in text mode (default)");
+    println!("  -z, --zero            end each output line with NUL, not newline,");
+    println!("                          and disable file name escaping");
+    println!();
+    println!("The following five options are useful only when verifying checksums:");
+    println!("      --ignore-missing  don't fail or report status for missing files");
+    println!("      --quiet           don't print OK for each successfully verified file");
+    println!("      --status          don't output anything, status code shows success");
+    println!("      --strict          exit non-zero for improperly formatted checksum lines");
+    println!("  -w, --warn            warn about improperly formatted checksum lines");
+    println!();
+    println!("      --help        display this help and exit");
+    println!("      --version     output version information and exit");
+    println!();
+    println!("The sums are computed as described in FIPS-180-4.");
+    println!("The default mode is to print a line with: checksum, a space,");
+    println!("a character indicating input mode ('*' for binary, ' ' for text");
+    println!("or where binary is insignificant), and name for each FILE.");
+    println!();
+    println!("When checking, the input should be a former output of this program,");
+    println!("or equivalent standalone program.  SHA384, SHA512, SHA512t224");
+    println!("and SHA512t256 lines may be mixed.");
+}
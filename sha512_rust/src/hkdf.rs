@@ -0,0 +1,194 @@
+// HKDF-SHA512（RFC 5869）による鍵導出
+
+use crate::HmacSha512;
+
+/// SHA-512の出力長（HashLen）
+const HASH_LEN: usize = 64;
+
+/// HKDFの処理で発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HkdfError {
+    /// 要求された出力長が 255 * HashLen（16320バイト）を超えている
+    InvalidLength(usize),
+    /// 外部から与えられたPRKがHashLenより短い
+    InvalidPrkLength(usize),
+}
+
+impl std::fmt::Display for HkdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HkdfError::InvalidLength(len) => write!(
+                f,
+                "HKDF: output length {} exceeds the maximum of {} bytes",
+                len,
+                255 * HASH_LEN
+            ),
+            HkdfError::InvalidPrkLength(len) => write!(
+                f,
+                "HKDF: PRK length {} is shorter than the hash length {}",
+                len, HASH_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HkdfError {}
+
+/// HKDF-SHA512のコンテキスト
+///
+/// Extract段階で得た擬似乱数鍵（PRK）でHMACを鍵付けした状態を保持し、
+/// Expand段階では任意の `info` に対して何度でも鍵素材を導出できます。
+#[derive(Clone)]
+pub struct Hkdf {
+    prk: Vec<u8>,
+    hmac: HmacSha512,   // PRKで鍵付け済みのHMAC
+}
+
+impl Hkdf {
+    /// Extract段階: `salt` と入力鍵素材 `ikm` からPRKを計算
+    /// `salt` が `None` の場合は、HashLenバイトの0をソルトとして使用します。
+    pub fn extract(salt: Option<&[u8]>, ikm: &[u8]) -> Self {
+        let mut mac = HmacSha512::new(salt.unwrap_or(&[0u8; HASH_LEN]));
+        mac.update(ikm);
+        let prk = mac.finalize();
+
+        Self {
+            prk: prk.to_vec(),
+            hmac: HmacSha512::new(&prk),
+        }
+    }
+
+    /// 既に計算済みのPRKからコンテキストを作成（Extract段階を省略する場合）
+    pub fn from_prk(prk: &[u8]) -> Result<Self, HkdfError> {
+        if prk.len() < HASH_LEN {
+            return Err(HkdfError::InvalidPrkLength(prk.len()));
+        }
+
+        Ok(Self {
+            prk: prk.to_vec(),
+            hmac: HmacSha512::new(prk),
+        })
+    }
+
+    /// 擬似乱数鍵（PRK）を返す
+    pub fn prk(&self) -> &[u8] {
+        &self.prk
+    }
+
+    /// Expand段階: `info` に紐づく鍵素材で `okm` 全体を埋める
+    /// `okm` の長さが 255 * HashLen を超える場合はエラーを返し、`okm` は変更しません。
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), HkdfError> {
+        if okm.len() > 255 * HASH_LEN {
+            return Err(HkdfError::InvalidLength(okm.len()));
+        }
+
+        // T(i) = HMAC(PRK, T(i-1) || info || i) を連結して出力する
+        let mut prev = [0u8; HASH_LEN];
+        for (i, chunk) in okm.chunks_mut(HASH_LEN).enumerate() {
+            let mut mac = self.hmac.clone();
+            if i > 0 {
+                mac.update(&prev);
+            }
+            mac.update(info);
+            mac.update(&[(i + 1) as u8]);
+            prev = mac.finalize();
+
+            chunk.copy_from_slice(&prev[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    // RFC 5869 にはSHA-512のテストケースがないため、以下はRFC 5869の各テストケースの入力に
+    // 対して独立した実装（Python hashlib/hmac）で計算した値を期待値としています。
+
+    // RFC 5869 テストケース1の入力（基本的なテスト）
+    #[test]
+    fn test_hkdf_sha512_rfc5869_case1() {
+        let hkdf = Hkdf::extract(Some(&hex("000102030405060708090a0b0c")), &[0x0b; 22]);
+        assert_eq!(
+            hkdf.prk(),
+            hex("665799823737ded04a88e47e54a5890bb2c3d247c7a4254a8e61350723590a26\
+                 c36238127d8661b88cf80ef802d57e2f7cebcf1e00e083848be19929c61b4237")
+        );
+
+        let mut okm = [0u8; 42];
+        hkdf.expand(&hex("f0f1f2f3f4f5f6f7f8f9"), &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c1481579338da362cb8d9f925d7cb")
+        );
+    }
+
+    // RFC 5869 テストケース2の入力（長い入力・出力）
+    #[test]
+    fn test_hkdf_sha512_rfc5869_case2() {
+        let salt: Vec<u8> = (0x60..=0xaf).collect();
+        let ikm: Vec<u8> = (0x00..=0x4f).collect();
+        let info: Vec<u8> = (0xb0..=0xff).collect();
+
+        let hkdf = Hkdf::extract(Some(&salt), &ikm);
+        assert_eq!(
+            hkdf.prk(),
+            hex("35672542907d4e142c00e84499e74e1de08be86535f924e022804ad775dde27e\
+                 c86cd1e5b7d178c74489bdbeb30712beb82d4f97416c5a94ea81ebdf3e629e4a")
+        );
+
+        let mut okm = [0u8; 82];
+        hkdf.expand(&info, &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("ce6c97192805b346e6161e821ed165673b84f400a2b514b2fe23d84cd189ddf1\
+                 b695b48cbd1c8388441137b3ce28f16aa64ba33ba466b24df6cfcb021ecff235\
+                 f6a2056ce3af1de44d572097a8505d9e7a93")
+        );
+    }
+
+    // RFC 5869 テストケース3の入力（ソルト・infoが空）
+    #[test]
+    fn test_hkdf_sha512_rfc5869_case3() {
+        let hkdf = Hkdf::extract(Some(&[]), &[0x0b; 22]);
+        assert_eq!(
+            hkdf.prk(),
+            hex("fd200c4987ac491313bd4a2a13287121247239e11c9ef82802044b66ef357e5b\
+                 194498d0682611382348572a7b1611de54764094286320578a863f36562b0df6")
+        );
+
+        let mut okm = [0u8; 42];
+        hkdf.expand(&[], &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("f5fa02b18298a72a8c23898a8703472c6eb179dc204c03425c970e3b164bf90fff22d04836d0e2343bac")
+        );
+
+        // ソルト省略時は HashLen バイトの0と同じ結果になる
+        let unsalted = Hkdf::extract(None, &[0x0b; 22]);
+        assert_eq!(unsalted.prk(), hkdf.prk());
+    }
+
+    // 出力長の上限（255 * HashLen）を超えるとエラーになること
+    #[test]
+    fn test_hkdf_sha512_length_limit() {
+        let hkdf = Hkdf::extract(None, b"input key material");
+
+        let mut max = vec![0u8; 255 * HASH_LEN];
+        assert!(hkdf.expand(b"info", &mut max).is_ok());
+
+        let mut too_long = vec![0u8; 255 * HASH_LEN + 1];
+        assert_eq!(
+            hkdf.expand(b"info", &mut too_long),
+            Err(HkdfError::InvalidLength(255 * HASH_LEN + 1))
+        );
+        assert!(too_long.iter().all(|&b| b == 0));
+
+        assert_eq!(
+            Hkdf::from_prk(&[0u8; HASH_LEN - 1]).err(),
+            Some(HkdfError::InvalidPrkLength(HASH_LEN - 1))
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// HMAC-SHA512（RFC 2104）による鍵付きハッシュ
+
+use crate::Sha512;
+
+/// SHA-512のブロック長（バイト）
+const BLOCK_LEN: usize = 128;
+
+/// ストリーミング対応のHMAC-SHA512コンテキスト
+///
+/// 鍵から導出した内側（ipad）・外側（opad）のハッシュ状態を生成時に計算して保持します。
+/// 同じ鍵で複数のメッセージを処理する場合は、生成済みのコンテキストを `clone` して使い回せます。
+#[derive(Clone)]
+pub struct HmacSha512 {
+    inner: Sha512,   // 鍵 XOR ipad を処理済みの内側ハッシュ
+    outer: Sha512,   // 鍵 XOR opad を処理済みの外側ハッシュ
+}
+
+impl HmacSha512 {
+    /// 任意の長さの鍵からHMACコンテキストを作成
+    /// ブロック長（128バイト）を超える鍵は、先にSHA-512でハッシュ化してから使用します。
+    pub fn new(key: &[u8]) -> Self {
+        let mut key_block = [0u8; BLOCK_LEN];
+        if key.len() > BLOCK_LEN {
+            let mut hasher = Sha512::new();
+            hasher.update(key);
+            key_block[..64].copy_from_slice(&hasher.finalize());
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        // 内側パッド (0x36) と外側パッド (0x5c) を鍵にXORしたブロックを作成
+        let mut ipad = [0x36u8; BLOCK_LEN];
+        let mut opad = [0x5cu8; BLOCK_LEN];
+        for i in 0..BLOCK_LEN {
+            ipad[i] ^= key_block[i];
+            opad[i] ^= key_block[i];
+        }
+
+        let mut inner = Sha512::new();
+        inner.update(&ipad);
+        let mut outer = Sha512::new();
+        outer.update(&opad);
+
+        Self { inner, outer }
+    }
+
+    /// メッセージデータを供給し、内側ハッシュを更新
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// 64バイトの認証タグを出力
+    pub fn finalize(self) -> [u8; 64] {
+        let inner_hash = self.inner.finalize();
+        let mut outer = self.outer;
+        outer.update(&inner_hash);
+        outer.finalize()
+    }
+
+    /// 計算したタグと与えられたタグを定数時間で比較
+    /// 長さが異なる場合は常に `false` を返します。
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time_eq(&self.finalize(), tag)
+    }
+}
+
+/// タイミング攻撃を避けるため、内容によらず全バイトを比較する
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn hmac(key: &[u8], data: &[u8]) -> [u8; 64] {
+        let mut mac = HmacSha512::new(key);
+        mac.update(data);
+        mac.finalize()
+    }
+
+    // RFC 4231 テストケース1
+    #[test]
+    fn test_hmac_sha512_rfc4231_case1() {
+        let result = hmac(&[0x0b; 20], b"Hi There");
+        assert_eq!(
+            result.to_vec(),
+            hex("87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854")
+        );
+    }
+
+    // RFC 4231 テストケース2（鍵長が出力長より短い場合）
+    #[test]
+    fn test_hmac_sha512_rfc4231_case2() {
+        let result = hmac(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            result.to_vec(),
+            hex("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737")
+        );
+    }
+
+    // RFC 4231 テストケース3
+    #[test]
+    fn test_hmac_sha512_rfc4231_case3() {
+        let result = hmac(&[0xaa; 20], &[0xdd; 50]);
+        assert_eq!(
+            result.to_vec(),
+            hex("fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb")
+        );
+    }
+
+    // RFC 4231 テストケース4
+    #[test]
+    fn test_hmac_sha512_rfc4231_case4() {
+        let key: Vec<u8> = (0x01..=0x19).collect();
+        let result = hmac(&key, &[0xcd; 50]);
+        assert_eq!(
+            result.to_vec(),
+            hex("b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3dba91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd")
+        );
+    }
+
+    // RFC 4231 テストケース5（出力を128ビットに切り詰め）
+    #[test]
+    fn test_hmac_sha512_rfc4231_case5() {
+        let result = hmac(&[0x0c; 20], b"Test With Truncation");
+        assert_eq!(result[..16].to_vec(), hex("415fad6271580a531d4179bc891d87a6"));
+    }
+
+    // RFC 4231 テストケース6（ブロック長を超える鍵）
+    #[test]
+    fn test_hmac_sha512_rfc4231_case6() {
+        let result = hmac(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(
+            result.to_vec(),
+            hex("80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598")
+        );
+    }
+
+    // RFC 4231 テストケース7（ブロック長を超える鍵とデータ）
+    #[test]
+    fn test_hmac_sha512_rfc4231_case7() {
+        let result = hmac(
+            &[0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size data. \
+              The key needs to be hashed before being used by the HMAC algorithm.",
+        );
+        assert_eq!(
+            result.to_vec(),
+            hex("e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58")
+        );
+    }
+
+    // 分割して供給しても一括の場合と同じタグになり、verifyが正しく判定すること
+    #[test]
+    fn test_hmac_sha512_streaming_and_verify() {
+        let mut mac = HmacSha512::new(b"Jefe");
+        mac.update(b"what do ya ");
+        mac.update(b"want for nothing?");
+        let expected =
+            hex("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737");
+        assert!(mac.clone().verify(&expected));
+
+        let mut wrong = expected.clone();
+        wrong[63] ^= 1;
+        assert!(!mac.clone().verify(&wrong));
+        assert!(!mac.verify(&expected[..32]));
+    }
+}
@@ -0,0 +1,147 @@
+// std::io との連携（Writeの実装と、読み書きしながらハッシュを計算するアダプタ）
+
+use std::io::{self, Read, Write};
+
+use crate::Sha512;
+
+/// `io::copy` などでハッシュ計算器へ直接データを書き込めるようにする
+impl Write for Sha512 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.update(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 読み込んだデータをそのまま返しつつ、SHA-512を計算するリーダー
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Sha512,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// 内側のリーダーをラップする
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha512::new(),
+        }
+    }
+
+    /// 内側のリーダーへの参照を返す
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// 内側のリーダーと、これまでに読み込んだデータのハッシュ値を返す
+    pub fn finish(self) -> (R, [u8; 64]) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// 書き込んだデータを内側のライターへ渡しつつ、SHA-512を計算するライター
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha512,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// 内側のライターをラップする
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha512::new(),
+        }
+    }
+
+    /// 内側のライターへの参照を返す
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// 内側のライターと、これまでに書き込んだデータのハッシュ値を返す
+    /// 内側のライターはフラッシュしないため、必要に応じて呼び出し側で `flush` してください。
+    pub fn finish(self) -> (W, [u8; 64]) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // 内側のライターが実際に受け付けたバイト数だけをハッシュに反映する
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn test_data() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn one_shot(data: &[u8]) -> [u8; 64] {
+        let mut hasher = Sha512::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    // io::copy でハッシュ計算器へ直接書き込めること
+    #[test]
+    fn test_io_copy_into_hasher() {
+        let data = test_data();
+        let mut hasher = Sha512::new();
+        io::copy(&mut Cursor::new(&data), &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), one_shot(&data));
+    }
+
+    // HashingReader は読み込んだデータを変更せずに返し、同じハッシュ値を計算すること
+    #[test]
+    fn test_hashing_reader() {
+        let data = test_data();
+        let mut reader = HashingReader::new(Cursor::new(&data));
+        let mut copied = Vec::new();
+        io::copy(&mut reader, &mut copied).unwrap();
+
+        let (inner, digest) = reader.finish();
+        assert_eq!(copied, data);
+        assert_eq!(inner.position(), data.len() as u64);
+        assert_eq!(digest, one_shot(&data));
+    }
+
+    // HashingWriter は書き込んだデータを内側へ渡し、同じハッシュ値を計算すること
+    #[test]
+    fn test_hashing_writer() {
+        let data = test_data();
+        let mut writer = HashingWriter::new(Vec::new());
+        io::copy(&mut Cursor::new(&data), &mut writer).unwrap();
+
+        let (inner, digest) = writer.finish();
+        assert_eq!(inner, data);
+        assert_eq!(digest, one_shot(&data));
+    }
+}
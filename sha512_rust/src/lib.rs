@@ -1,5 +1,37 @@
 // Rust用 SHA-512 ARMアセンブリ実装
 
+#[cfg(target_arch = "aarch64")]
+pub mod aarch64;
+#[cfg(target_arch = "arm")]
+pub mod arm;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+mod backend;
+mod hkdf;
+mod hmac;
+mod io;
+mod many;
+mod pbkdf2;
+mod serialize;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod ssse3;
+mod unrolled;
+
+#[cfg(target_arch = "aarch64")]
+pub use aarch64::Aarch64Sha3;
+#[cfg(target_arch = "arm")]
+pub use arm::ArmNeon;
+pub use backend::{backends, best_backend, BlockCompressor, Generic};
+pub use hkdf::{Hkdf, HkdfError};
+pub use hmac::HmacSha512;
+pub use io::{HashingReader, HashingWriter};
+pub use many::{hash_many, hash_many_with, ManyBackend};
+pub use pbkdf2::{pbkdf2, Pbkdf2Error};
+pub use serialize::StateError;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use ssse3::{Avx, Ssse3};
+pub use unrolled::Unrolled;
+
 /// SHA-512の状態を保持する構造体（64ビットワード × 8本）
 /// メッセージダイジェストの途中経過や最終結果（H0〜H7）を格納します。
 #[repr(C)]
@@ -25,6 +57,114 @@ impl Sha512State {
             ],
         }
     }
+
+    /// SHA-384の初期化ベクトル（IV）で初期状態を生成します（FIPS 180-4 §5.3.4）。
+    /// 9番目から16番目の素数の平方根の小数部分から派生しています。
+    pub fn new_384() -> Self {
+        Self {
+            h: [
+                0xcbbb9d5dc1059ed8,
+                0x629a292a367cd507,
+                0x9159015a3070dd17,
+                0x152fecd8f70e5939,
+                0x67332667ffc00b31,
+                0x8eb44a8768581511,
+                0xdb0c2e0d64f98fa7,
+                0x47b5481dbefa4fa4,
+            ],
+        }
+    }
+
+    /// SHA-512/224の初期化ベクトル（IV）で初期状態を生成します（FIPS 180-4 §5.3.6.1）。
+    /// SHA-512/t IV生成関数に t = 224 を与えて得られる値です。
+    pub fn new_512_224() -> Self {
+        Self {
+            h: [
+                0x8c3d37c819544da2,
+                0x73e1996689dcd4d6,
+                0x1dfab7ae32ff9c82,
+                0x679dd514582f9fcf,
+                0x0f6d2b697bd44da8,
+                0x77e36f7304c48942,
+                0x3f9d85a86a1d36c8,
+                0x1112e6ad91d692a1,
+            ],
+        }
+    }
+
+    /// SHA-512/256の初期化ベクトル（IV）で初期状態を生成します（FIPS 180-4 §5.3.6.2）。
+    /// SHA-512/t IV生成関数に t = 256 を与えて得られる値です。
+    pub fn new_512_256() -> Self {
+        Self {
+            h: [
+                0x22312194fc2bf72c,
+                0x9f555fa3c84c64c2,
+                0x2393b86b6f53b151,
+                0x963877195940eabd,
+                0x96283ee2a88effe3,
+                0xbe5e1e2553863992,
+                0x2b0199fc2c85b8aa,
+                0x0eb72ddc81c52ca2,
+            ],
+        }
+    }
+    
+    /// SHA-512/t の初期化ベクトル（IV）を生成します（FIPS 180-4 §5.3.6）。
+    /// 
+    /// SHA-512のIVの各ワードを `0xa5a5a5a5a5a5a5a5` とXORした状態を初期値として、
+    /// ASCII文字列 "SHA-512/t"（tは10進表記）をSHA-512で処理した結果の内部状態がIVになります。
+    /// t = 384 および t ≥ 512 は規格上禁止されているため、エラーを返します。
+    pub fn for_truncation(t: usize) -> Result<Self, TruncationError> {
+        validate_truncation(t)?;
+        
+        let mut iv = Self::new();
+        for word in iv.h.iter_mut() {
+            *word ^= 0xa5a5a5a5a5a5a5a5;
+        }
+        
+        let mut hasher = Sha512::with_state(iv);
+        hasher.update(format!("SHA-512/{}", t).as_bytes());
+        Ok(hasher.finalize_state())
+    }
+}
+
+impl Default for Sha512State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-512/t の出力ビット長 t が不正な場合のエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationError {
+    /// t = 0（出力が空になる）
+    Zero,
+    /// t = 384（SHA-384との混同を避けるため規格上禁止）
+    Reserved384,
+    /// t ≥ 512（SHA-512の出力長を超える、または切り詰めにならない）
+    TooLarge(usize),
+}
+
+impl std::fmt::Display for TruncationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TruncationError::Zero => write!(f, "SHA-512/t: t must be greater than 0"),
+            TruncationError::Reserved384 => write!(f, "SHA-512/t: t = 384 is not permitted"),
+            TruncationError::TooLarge(t) => write!(f, "SHA-512/t: t = {} must be less than 512", t),
+        }
+    }
+}
+
+impl std::error::Error for TruncationError {}
+
+/// SHA-512/t で許可された出力ビット長かどうかを検査
+fn validate_truncation(t: usize) -> Result<(), TruncationError> {
+    match t {
+        0 => Err(TruncationError::Zero),
+        384 => Err(TruncationError::Reserved384),
+        t if t >= 512 => Err(TruncationError::TooLarge(t)),
+        _ => Ok(()),
+    }
 }
 
 /// SHA-512の各ラウンドで使用される80個の定数K
@@ -55,12 +195,18 @@ const K: [u64; 80] = [
 /// インラインアセンブリを使用したSHA-512変換関数
 /// 
 /// 1回につき128バイト（1024ビット）のブロックを処理します。
+/// NEONに対応したCPUではlibgcryptと同じ方式のNEON実装（`arm` モジュール）を、
+/// 対応していないCPUでは汎用実装を使用します。
+///
+/// # Safety
+/// 呼び出し側が満たすべき前提条件はありません（従来のシグネチャとの互換性のため `unsafe` のままにしています）。
 #[cfg(target_arch = "arm")]
 pub unsafe fn sha512_transform_arm(state: &mut Sha512State, data: &[u8; 128]) {
-    // 注: 現時点ではアセンブリのプレースホルダとして汎用実装を呼び出しています。
-    // 本来はここにlibgcryptから移植した最適化済みARMアセンブリを記述します。
-    
-    sha512_transform_generic(state, data);
+    if arm::is_supported() {
+        arm::compress512(&mut state.h, std::slice::from_ref(data));
+    } else {
+        sha512_transform_generic(state, data);
+    }
 }
 
 /// 非ARM環境向けの公開エクスポート（テスト等の互換性用）
@@ -148,24 +294,48 @@ pub fn sha512_transform_generic(state: &mut Sha512State, data: &[u8; 128]) {
 }
 
 /// 完全なハッシュ値を算出するためのSHA-512コンテキスト
+#[derive(Clone)]
 pub struct Sha512 {
     state: Sha512State,    // 現在のハッシュ状態
     buffer: [u8; 128],     // 未処理データを一時保持するバッファ
     buffer_len: usize,     // バッファ内のデータ長
     total_len: u128,       // これまでに処理したデータの総バイト長
+    backend: &'static dyn BlockCompressor,   // ブロックの圧縮に使用するバックエンド
 }
 
 impl Sha512 {
     /// 新しいSHA-512コンテキストを初期状態で作成します。
     pub fn new() -> Self {
+        Self::with_state(Sha512State::new())
+    }
+    
+    /// 圧縮関数のバックエンドを指定してコンテキストを作成します。
+    /// 実行中のCPUでそのバックエンドが利用できない場合は `None` を返します。
+    pub fn with_backend(backend: &'static dyn BlockCompressor) -> Option<Self> {
+        if !backend.is_available() {
+            return None;
+        }
+        let mut hasher = Self::new();
+        hasher.backend = backend;
+        Some(hasher)
+    }
+    
+    /// 任意の初期状態からコンテキストを作成します（SHA-384などの派生アルゴリズム用）。
+    fn with_state(state: Sha512State) -> Self {
         Self {
-            state: Sha512State::new(),
+            state,
             buffer: [0; 128],
             buffer_len: 0,
             total_len: 0,
+            backend: best_backend(),
         }
     }
     
+    /// このコンテキストが使用している圧縮関数のバックエンドを返します。
+    pub fn backend(&self) -> &'static dyn BlockCompressor {
+        self.backend
+    }
+    
     /// 任意の長さのデータを受け取り、内部状態を更新します。
     pub fn update(&mut self, data: &[u8]) {
         let mut pos = 0;
@@ -181,29 +351,47 @@ impl Sha512 {
             
             // バッファが1ブロック分（128バイト）埋まったら変換実行
             if self.buffer_len == 128 {
-                sha512_transform_generic(&mut self.state, &self.buffer);
+                self.backend.compress(&mut self.state, &[self.buffer]);
                 self.buffer_len = 0;
             }
         }
         
-        // 128バイト単位の完全なブロックを直接処理（高速化）
-        while pos + 128 <= data.len() {
-            let mut block = [0u8; 128];
-            block.copy_from_slice(&data[pos..pos + 128]);
-            sha512_transform_generic(&mut self.state, &block);
-            pos += 128;
+        // 128バイト単位の完全なブロックが連続する部分は、入力スライスからコピーせずにまとめて圧縮
+        let (blocks, rest) = data[pos..].as_chunks::<128>();
+        if !blocks.is_empty() {
+            self.backend.compress(&mut self.state, blocks);
         }
         
         // 1ブロックに満たない残りのデータをバッファに格納
-        if pos < data.len() {
-            let remaining = data.len() - pos;
-            self.buffer[..remaining].copy_from_slice(&data[pos..]);
-            self.buffer_len = remaining;
+        if !rest.is_empty() {
+            self.buffer[..rest.len()].copy_from_slice(rest);
+            self.buffer_len = rest.len();
         }
     }
     
     /// パディング処理を施し、最終的な64バイトのハッシュ値を返します。
-    pub fn finalize(mut self) -> [u8; 64] {
+    pub fn finalize(self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        self.finalize_into(&mut result);
+        result
+    }
+    
+    /// パディング処理を施し、ハッシュ値の先頭 `out.len()` バイトを書き込みます。
+    /// SHA-384やSHA-512/tのような切り詰め出力の派生アルゴリズムで共通に使用します。
+    fn finalize_into(self, out: &mut [u8]) {
+        let state = self.finalize_state();
+        
+        // --- 最終ハッシュ値の出力 ---
+        // 内部状態（8個のu64）をバイト配列に変換し、必要な長さだけ書き出す
+        let mut result = [0u8; 64];
+        for i in 0..8 {
+            result[i * 8..(i + 1) * 8].copy_from_slice(&state.h[i].to_be_bytes());
+        }
+        out.copy_from_slice(&result[..out.len()]);
+    }
+    
+    /// パディングと長さ情報を処理し、最終ブロック処理後の内部状態を返します。
+    fn finalize_state(mut self) -> Sha512State {
         let bit_len = self.total_len * 8;
         
         // --- パディングの開始 ---
@@ -217,7 +405,7 @@ impl Sha512 {
                 self.buffer[self.buffer_len] = 0;
                 self.buffer_len += 1;
             }
-            sha512_transform_generic(&mut self.state, &self.buffer);
+            self.backend.compress(&mut self.state, &[self.buffer]);
             self.buffer_len = 0;
         }
         
@@ -229,18 +417,176 @@ impl Sha512 {
         
         // 最後の128ビット（16バイト）に、データの総ビット長（ビッグエンディアン）を書き込む
         self.buffer[112..128].copy_from_slice(&bit_len.to_be_bytes());
-        sha512_transform_generic(&mut self.state, &self.buffer);
+        self.backend.compress(&mut self.state, &[self.buffer]);
         
-        // --- 最終ハッシュ値の出力 ---
-        // 内部状態（8個のu64）をバイト配列に変換
-        let mut result = [0u8; 64];
-        for i in 0..8 {
-            result[i * 8..(i + 1) * 8].copy_from_slice(&self.state.h[i].to_be_bytes());
+        self.state
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-384コンテキスト
+/// 圧縮関数はSHA-512と共通で、初期化ベクトルと出力長（48バイト）のみが異なります。
+#[derive(Clone)]
+pub struct Sha384 {
+    inner: Sha512,
+}
+
+impl Sha384 {
+    /// 新しいSHA-384コンテキストを初期状態で作成します。
+    pub fn new() -> Self {
+        Self {
+            inner: Sha512::with_state(Sha512State::new_384()),
+        }
+    }
+    
+    /// 任意の長さのデータを受け取り、内部状態を更新します。
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+    
+    /// パディング処理を施し、48バイトに切り詰めたハッシュ値を返します。
+    pub fn finalize(self) -> [u8; 48] {
+        let mut result = [0u8; 48];
+        self.inner.finalize_into(&mut result);
+        result
+    }
+}
+
+impl Default for Sha384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-512/224コンテキスト
+/// SHA-512の圧縮関数を専用のIVで実行し、出力を224ビット（28バイト）に切り詰めます。
+#[derive(Clone)]
+pub struct Sha512_224 {
+    inner: Sha512,
+}
+
+impl Sha512_224 {
+    /// 新しいSHA-512/224コンテキストを初期状態で作成します。
+    pub fn new() -> Self {
+        Self {
+            inner: Sha512::with_state(Sha512State::new_512_224()),
+        }
+    }
+    
+    /// 任意の長さのデータを受け取り、内部状態を更新します。
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+    
+    /// パディング処理を施し、28バイトに切り詰めたハッシュ値を返します。
+    pub fn finalize(self) -> [u8; 28] {
+        let mut result = [0u8; 28];
+        self.inner.finalize_into(&mut result);
+        result
+    }
+}
+
+impl Default for Sha512_224 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-512/256コンテキスト
+/// SHA-512の圧縮関数を専用のIVで実行し、出力を256ビット（32バイト）に切り詰めます。
+#[derive(Clone)]
+pub struct Sha512_256 {
+    inner: Sha512,
+}
+
+impl Sha512_256 {
+    /// 新しいSHA-512/256コンテキストを初期状態で作成します。
+    pub fn new() -> Self {
+        Self {
+            inner: Sha512::with_state(Sha512State::new_512_256()),
+        }
+    }
+    
+    /// 任意の長さのデータを受け取り、内部状態を更新します。
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+    
+    /// パディング処理を施し、32バイトに切り詰めたハッシュ値を返します。
+    pub fn finalize(self) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        self.inner.finalize_into(&mut result);
+        result
+    }
+}
+
+impl Default for Sha512_256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 任意の出力ビット長 t に対応したSHA-512/tコンテキスト
+/// IVは `Sha512State::for_truncation` で生成し、出力は先頭tビットに切り詰めます。
+#[derive(Clone)]
+pub struct Sha512T {
+    inner: Sha512,
+    t: usize,
+}
+
+impl Sha512T {
+    /// 出力ビット長 t を指定してSHA-512/tコンテキストを作成します。
+    pub fn new(t: usize) -> Result<Self, TruncationError> {
+        let iv = Sha512State::for_truncation(t)?;
+        Ok(Self {
+            inner: Sha512::with_state(iv),
+            t,
+        })
+    }
+    
+    /// 出力ビット長 t を返します。
+    pub fn bits(&self) -> usize {
+        self.t
+    }
+    
+    /// 出力バイト長（tビットを切り上げたバイト数）を返します。
+    pub fn output_len(&self) -> usize {
+        self.t.div_ceil(8)
+    }
+    
+    /// 任意の長さのデータを受け取り、内部状態を更新します。
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+    
+    /// パディング処理を施し、先頭tビットのハッシュ値を返します。
+    /// tが8の倍数でない場合、最終バイトの余りの下位ビットは0になります。
+    pub fn finalize(self) -> Vec<u8> {
+        let mut result = vec![0u8; self.output_len()];
+        let rem = self.t % 8;
+        self.inner.finalize_into(&mut result);
+        if rem != 0 {
+            let last = result.len() - 1;
+            result[last] &= 0xffu8 << (8 - rem);
         }
         result
     }
 }
 
+/// テストで使用する16進文字列のデコード（テストベクタの記述用）
+#[cfg(test)]
+pub(crate) fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,4 +633,218 @@ mod tests {
         
         assert_eq!(result, expected);
     }
-}
\ No newline at end of file
+    
+    // 空文字 "" のSHA-384期待値テスト
+    #[test]
+    fn test_sha384_empty() {
+        let mut hasher = Sha384::new();
+        hasher.update(b"");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x38, 0xb0, 0x60, 0xa7, 0x51, 0xac, 0x96, 0x38,
+            0x4c, 0xd9, 0x32, 0x7e, 0xb1, 0xb1, 0xe3, 0x6a,
+            0x21, 0xfd, 0xb7, 0x11, 0x14, 0xbe, 0x07, 0x43,
+            0x4c, 0x0c, 0xc7, 0xbf, 0x63, 0xf6, 0xe1, 0xda,
+            0x27, 0x4e, 0xde, 0xbf, 0xe7, 0x6f, 0x65, 0xfb,
+            0xd5, 0x1a, 0xd2, 0xf1, 0x48, 0x98, 0xb9, 0x5b,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 文字列 "abc" のSHA-384期待値テスト
+    #[test]
+    fn test_sha384_abc() {
+        let mut hasher = Sha384::new();
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b,
+            0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07,
+            0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63,
+            0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed,
+            0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23,
+            0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 2ブロックにまたがる896ビットメッセージのSHA-384期待値テスト
+    #[test]
+    fn test_sha384_two_blocks() {
+        let mut hasher = Sha384::new();
+        hasher.update(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x09, 0x33, 0x0c, 0x33, 0xf7, 0x11, 0x47, 0xe8,
+            0x3d, 0x19, 0x2f, 0xc7, 0x82, 0xcd, 0x1b, 0x47,
+            0x53, 0x11, 0x1b, 0x17, 0x3b, 0x3b, 0x05, 0xd2,
+            0x2f, 0xa0, 0x80, 0x86, 0xe3, 0xb0, 0xf7, 0x12,
+            0xfc, 0xc7, 0xc7, 0x1a, 0x55, 0x7e, 0x2d, 0xb9,
+            0x66, 0xc3, 0xe9, 0xfa, 0x91, 0x74, 0x60, 0x39,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 空文字 "" のSHA-512/224期待値テスト
+    #[test]
+    fn test_sha512_224_empty() {
+        let mut hasher = Sha512_224::new();
+        hasher.update(b"");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x6e, 0xd0, 0xdd, 0x02, 0x80, 0x6f, 0xa8, 0x9e,
+            0x25, 0xde, 0x06, 0x0c, 0x19, 0xd3, 0xac, 0x86,
+            0xca, 0xbb, 0x87, 0xd6, 0xa0, 0xdd, 0xd0, 0x5c,
+            0x33, 0x3b, 0x84, 0xf4,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 文字列 "abc" のSHA-512/224期待値テスト
+    #[test]
+    fn test_sha512_224_abc() {
+        let mut hasher = Sha512_224::new();
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x46, 0x34, 0x27, 0x0f, 0x70, 0x7b, 0x6a, 0x54,
+            0xda, 0xae, 0x75, 0x30, 0x46, 0x08, 0x42, 0xe2,
+            0x0e, 0x37, 0xed, 0x26, 0x5c, 0xee, 0xe9, 0xa4,
+            0x3e, 0x89, 0x24, 0xaa,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 空文字 "" のSHA-512/256期待値テスト
+    #[test]
+    fn test_sha512_256_empty() {
+        let mut hasher = Sha512_256::new();
+        hasher.update(b"");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0xc6, 0x72, 0xb8, 0xd1, 0xef, 0x56, 0xed, 0x28,
+            0xab, 0x87, 0xc3, 0x62, 0x2c, 0x51, 0x14, 0x06,
+            0x9b, 0xdd, 0x3a, 0xd7, 0xb8, 0xf9, 0x73, 0x74,
+            0x98, 0xd0, 0xc0, 0x1e, 0xce, 0xf0, 0x96, 0x7a,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 文字列 "abc" のSHA-512/256期待値テスト
+    #[test]
+    fn test_sha512_256_abc() {
+        let mut hasher = Sha512_256::new();
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        let expected = [
+            0x53, 0x04, 0x8e, 0x26, 0x81, 0x94, 0x1e, 0xf9,
+            0x9b, 0x2e, 0x29, 0xb7, 0x6b, 0x4c, 0x7d, 0xab,
+            0xe4, 0xc2, 0xd0, 0xc6, 0x34, 0xfc, 0x6d, 0x46,
+            0xe0, 0xe2, 0xf1, 0x31, 0x07, 0xe7, 0xaf, 0x23,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // SHA-512/t IV生成関数が規格に掲載されたSHA-512/224のIVと一致すること
+    #[test]
+    fn test_sha512_t_iv_224() {
+        let iv = Sha512State::for_truncation(224).unwrap();
+        assert_eq!(iv.h, Sha512State::new_512_224().h);
+    }
+    
+    // SHA-512/t IV生成関数が規格に掲載されたSHA-512/256のIVと一致すること
+    #[test]
+    fn test_sha512_t_iv_256() {
+        let iv = Sha512State::for_truncation(256).unwrap();
+        assert_eq!(iv.h, Sha512State::new_512_256().h);
+    }
+    
+    // 禁止されたtに対して型付きエラーが返ること
+    #[test]
+    fn test_sha512_t_invalid() {
+        assert_eq!(Sha512T::new(0).err(), Some(TruncationError::Zero));
+        assert_eq!(Sha512T::new(384).err(), Some(TruncationError::Reserved384));
+        assert_eq!(Sha512T::new(512).err(), Some(TruncationError::TooLarge(512)));
+        assert_eq!(Sha512T::new(1000).err(), Some(TruncationError::TooLarge(1000)));
+    }
+    
+    // Sha512T(256) の出力が固定IV版のSHA-512/256と一致すること
+    #[test]
+    fn test_sha512_t_matches_fixed_variant() {
+        let mut hasher = Sha512T::new(256).unwrap();
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        let mut fixed = Sha512_256::new();
+        fixed.update(b"abc");
+        
+        assert_eq!(result, fixed.finalize());
+    }
+    
+    // tが8の倍数でない場合、切り上げたバイト数を返し余りのビットが0になること
+    #[test]
+    fn test_sha512_t_non_byte_aligned() {
+        let mut hasher = Sha512T::new(12).unwrap();
+        assert_eq!(hasher.output_len(), 2);
+        hasher.update(b"abc");
+        let result = hasher.finalize();
+        
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1] & 0x0f, 0);
+    }
+    
+    // 100万文字の "a"（FIPS 180-4 のテストベクトル）を1回の update で処理する
+    #[test]
+    fn test_sha512_million_a() {
+        let mut hasher = Sha512::new();
+        hasher.update(&vec![b'a'; 1_000_000]);
+        let result = hasher.finalize();
+        
+        let expected = [
+            0xe7, 0x18, 0x48, 0x3d, 0x0c, 0xe7, 0x69, 0x64,
+            0x4e, 0x2e, 0x42, 0xc7, 0xbc, 0x15, 0xb4, 0x63,
+            0x8e, 0x1f, 0x98, 0xb1, 0x3b, 0x20, 0x44, 0x28,
+            0x56, 0x32, 0xa8, 0x03, 0xaf, 0xa9, 0x73, 0xeb,
+            0xde, 0x0f, 0xf2, 0x44, 0x87, 0x7e, 0xa6, 0x0a,
+            0x4c, 0xb0, 0x43, 0x2c, 0xe5, 0x77, 0xc3, 0x1b,
+            0xeb, 0x00, 0x9c, 0x5c, 0x2c, 0x49, 0xaa, 0x2e,
+            0x4e, 0xad, 0xb2, 0x17, 0xad, 0x8c, 0xc0, 0x9b,
+        ];
+        
+        assert_eq!(result, expected);
+    }
+    
+    // 入力をどこで分割して update しても、まとめて渡した場合と同じ結果になること
+    #[test]
+    fn test_sha512_split_updates() {
+        let data: Vec<u8> = (0..128 * 5 + 17).map(|i| (i * 31 + 7) as u8).collect();
+        let mut hasher = Sha512::new();
+        hasher.update(&data);
+        let expected = hasher.finalize();
+        
+        for first in [0, 1, 128 - 1, 128, 128 + 1, 128 * 2, 128 * 3 + 5] {
+            for second in [0, 1, 128, 128 * 2 - 3] {
+                let second = (first + second).min(data.len());
+                let mut hasher = Sha512::new();
+                hasher.update(&data[..first]);
+                hasher.update(&data[first..second]);
+                hasher.update(&data[second..]);
+                assert_eq!(hasher.finalize(), expected, "{} / {}", first, second);
+            }
+        }
+    }
+}
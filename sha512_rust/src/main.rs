@@ -1,15 +1,479 @@
 // src/main.rs
 // SHA-512 純Rust版テストプログラム
 
-use sha512_arm::{Sha512State, sha512_transform_generic};
+use sha512_arm::{Sha512, Sha512State, sha512_transform_generic, pbkdf2, backends, best_backend, hash_many, hash_many_with, BlockCompressor, ManyBackend};
 use std::time::Instant;
+use bench_harness::{
+    parse_args, print_help, run_bench, write_report, BenchConfig, BenchOptions, BenchResult, CliCommand, CliSpec,
+    Defaults, Harness, OutputFormat, RunMetadata, Warmup,
+};
 use std::hint::black_box;
 
+/// 第1引数で選択するモード（省略時は圧縮関数のベンチマーク）
+const MODES: [&str; 5] = ["pbkdf2", "backends", "update", "many", "sweep"];
+
 fn main() {
     
-    println!("=== SHA-512 汎用実装 ===\n");
+    // 第1引数でモードを選択（省略時は圧縮関数のベンチマーク）
+    let args: Vec<String> = std::env::args().collect();
+    let prog = args.first().map_or("sha512_test", String::as_str);
+    let mode = args.get(1).map(String::as_str).filter(|arg| MODES.contains(arg));
+    let command = match mode {
+        Some(mode) => format!("{} {}", prog, mode),
+        None => prog.to_string(),
+    };
+    let mut rest: Vec<String> = args.iter().skip(if mode.is_some() { 2 } else { 1 }).cloned().collect();
+    
+    // pbkdf2 の反復回数は最初の位置引数で指定する（省略時は既定値）
+    let mut pbkdf2_iterations = PBKDF2_DEFAULT_ITERATIONS;
+    if mode == Some("pbkdf2") && rest.first().is_some_and(|arg| !arg.starts_with('-')) {
+        let arg = rest.remove(0);
+        pbkdf2_iterations = match arg.parse::<u32>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                eprintln!("エラー: 反復回数には1以上の整数を指定してください: {}", arg);
+                std::process::exit(2);
+            }
+        };
+    }
+    
+    // 反復回数・入力サイズ・バックエンド・出力形式などはすべてのモードで共通のオプションで指定
+    // （選択できるバックエンドや計測の既定値はモードごとに異なる）
+    let available: Vec<(&str, bool)> = backends()
+        .iter()
+        .map(|backend| (backend.name(), backend.is_available()))
+        .collect();
+    let spec = mode_spec(mode, &available);
+    let options = match parse_args(rest, &spec) {
+        Ok(CliCommand::Run(options)) => options,
+        Ok(CliCommand::Help) => {
+            print_help(&command, &spec);
+            println!();
+            match mode {
+                Some("pbkdf2") => println!(
+                    "反復回数: {} [反復回数] [オプション]...（既定: {}）",
+                    command, PBKDF2_DEFAULT_ITERATIONS
+                ),
+                Some("sweep") => println!(
+                    "--iterations と --warmup は128バイトのメッセージでの回数です（長いメッセージでは長さに反比例して減らします）"
+                ),
+                Some(_) => {}
+                None => {
+                    println!("その他のモード: {} pbkdf2 [反復回数] | backends | update | many | sweep", prog);
+                    println!("（各モードのオプションは '{} <モード> --help' を参照）", prog);
+                }
+            }
+            return;
+        }
+        Err(message) => {
+            eprintln!("エラー: {}", message);
+            eprintln!("詳しくは '{} --help' を参照してください", command);
+            std::process::exit(2);
+        }
+    };
+    
+    if options.format == OutputFormat::Human {
+        let title = match mode {
+            Some("pbkdf2") => "PBKDF2-HMAC-SHA-512 ベンチマーク",
+            Some("backends") => "SHA-512 バックエンド比較",
+            Some("update") => "SHA-512 大きなバッファの update スループット",
+            Some("many") => "SHA-512 多数メッセージの一括計算",
+            Some("sweep") => "SHA-512 メッセージサイズ別のスループット",
+            _ => "SHA-512 圧縮関数",
+        };
+        println!("=== {} ===\n", title);
+    }
+    
+    match mode {
+        Some("pbkdf2") => bench_pbkdf2(&options, pbkdf2_iterations),
+        Some("backends") => bench_backends(&options),
+        Some("update") => bench_update(&options),
+        Some("many") => bench_many(&options),
+        Some("sweep") => bench_sweep(&options),
+        // 特定の入力値を用いた正当性の検証とベンチマークの実行
+        _ => test_custom_values(&options),
+    }
+}
+
+/// --quick を指定した場合の試行回数（サンプル数）
+const QUICK_TRIALS: usize = 5;
+
+/// モードごとの、選択できるアルゴリズム・バックエンドと計測の既定値
+fn mode_spec<'a>(mode: Option<&str>, available: &'a [(&'a str, bool)]) -> CliSpec<'a> {
+    // 1回の呼び出しが長い処理は、ウォームアップ1回のあと1回ずつ計測する
+    let single_calls = |trials| Defaults {
+        normal: BenchConfig { warmup: Warmup::Iterations(1), samples: trials, iterations_per_sample: 1 },
+        quick: BenchConfig { warmup: Warmup::Iterations(1), samples: QUICK_TRIALS, iterations_per_sample: 1 },
+    };
+    let compress = CliSpec {
+        block_size: 128,
+        algorithms: &["sha512", "sha384", "sha512/224", "sha512/256"],
+        backends: available,
+        default_backend: "generic",
+        config: Defaults::default(),
+        size: Some(Defaults::same(128)),
+    };
+    match mode {
+        Some("pbkdf2") => {
+            // pbkdf2 は Sha512::new が選択するバックエンドのみを使用する
+            let best = available.iter().position(|&(name, _)| name == best_backend().name()).unwrap();
+            CliSpec {
+                algorithms: &["pbkdf2-hmac-sha512"],
+                backends: &available[best..=best],
+                default_backend: best_backend().name(),
+                config: single_calls(PBKDF2_TRIALS),
+                size: None,
+                ..compress
+            }
+        }
+        Some("backends") => CliSpec {
+            algorithms: &["sha512"],
+            default_backend: "all",
+            config: single_calls(BACKEND_TRIALS),
+            size: Some(Defaults::same(BACKEND_BLOCKS * 128)),
+            ..compress
+        },
+        Some("update") => CliSpec {
+            algorithms: &["sha512"],
+            default_backend: best_backend().name(),
+            config: single_calls(UPDATE_TRIALS),
+            size: Some(Defaults { normal: UPDATE_SIZES[UPDATE_SIZES.len() - 1], quick: UPDATE_QUICK_MAX_SIZE }),
+            ..compress
+        },
+        Some("many") => CliSpec {
+            algorithms: &["sha512"],
+            default_backend: "all",
+            config: single_calls(MANY_TRIALS),
+            size: None,
+            ..compress
+        },
+        Some("sweep") => CliSpec {
+            algorithms: &["sha512"],
+            default_backend: "all",
+            config: Defaults {
+                normal: sweep_config(SWEEP_BYTES_PER_SAMPLE, SWEEP_SAMPLES),
+                quick: sweep_config(SWEEP_QUICK_BYTES_PER_SAMPLE, QUICK_TRIALS),
+            },
+            size: Some(Defaults { normal: SWEEP_SIZES[SWEEP_SIZES.len() - 1], quick: SWEEP_QUICK_MAX_SIZE }),
+            ..compress
+        },
+        _ => compress,
+    }
+}
+
+/// 名前からバックエンドを取得（名前は `parse_args` で検証済み）
+fn backend_by_name(name: &str) -> &'static dyn BlockCompressor {
+    *backends().iter().find(|backend| backend.name() == name).unwrap()
+}
+
+/// 計測結果を --format で指定した形式で標準出力に書き出す
+fn report(options: &BenchOptions, results: &[BenchResult]) {
+    let metadata = RunMetadata::new(env!("CARGO_BIN_NAME"), options.algorithm, options.input_size, 128);
+    write_report(&mut std::io::stdout().lock(), options.format, &metadata, results)
+        .expect("結果の出力に失敗しました");
+}
+
+/// 中央値のサンプルから求めた1回の呼び出しあたりの時間（ナノ秒）
+fn median_call_ns(result: &BenchResult) -> f64 {
+    result.stats.median_ns / result.config.iterations_per_sample as f64
+}
+
+/// `sizes` のうち `max` より小さいものと `max`（--size で計測する最大のサイズを指定する）
+fn sizes_up_to(sizes: &[usize], max: usize) -> Vec<usize> {
+    let mut selected: Vec<usize> = sizes.iter().copied().filter(|&size| size < max).collect();
+    selected.push(max);
+    selected
+}
+
+/// PBKDF2ベンチマークの既定の反復回数
+const PBKDF2_DEFAULT_ITERATIONS: u32 = 100_000;
+
+/// PBKDF2ベンチマークの試行回数
+const PBKDF2_TRIALS: usize = 10;
+
+/// PBKDF2-HMAC-SHA-512の反復速度を測定
+/// 1ブロック分（64バイト）の鍵を指定回数の反復で導出する時間を計測し、
+/// 従来の表示の場合は1秒あたりの反復回数を併せて表示します。
+fn bench_pbkdf2(options: &BenchOptions, iterations: u32) {
+    let password = b"password";
+    let salt = b"saltSALTsaltSALT";
+    let mut output = [0u8; 64];
+    
+    let name = format!("{} (c={})", options.backends[0], iterations);
+    let result = run_bench(&name, 0, &options.config, || {
+        pbkdf2(black_box(password), black_box(salt), iterations, &mut output).unwrap();
+        black_box(output);
+    });
+    report(options, std::slice::from_ref(&result));
+    
+    // 1反復 = HMAC 1回 = 圧縮関数2回
+    if options.format == OutputFormat::Human {
+        println!();
+        println!("=== 1秒あたりの反復回数（中央値基準） ===");
+        println!("{:.0} 回/秒", iterations as f64 / (median_call_ns(&result) / 1e9));
+    }
+}
+
+
+/// バックエンド比較で1回の呼び出しに渡すブロック数の既定値（128KiB）
+const BACKEND_BLOCKS: usize = 1024;
+
+/// バックエンド比較の試行回数
+const BACKEND_TRIALS: usize = 200;
+
+/// 実行中のCPUで利用できる各バックエンドの圧縮関数の速度を比較
+/// --size で指定したバイト数（既定は128KiB）のブロック列をまとめて渡す処理を繰り返し計測します。
+/// AArch64・x86のどちらで実行しても同じ形式で出力されます。
+fn bench_backends(options: &BenchOptions) {
+    let blocks: Vec<[u8; 128]> = (0..options.input_size / 128)
+        .map(|n| std::array::from_fn(|i| (n * 128 + i) as u8))
+        .collect();
+    
+    // 基準となる汎用実装の結果
+    let mut expected = Sha512State::new();
+    for block in &blocks {
+        sha512_transform_generic(&mut expected, block);
+    }
+    
+    if options.format == OutputFormat::Human {
+        for backend in backends().iter().filter(|backend| !backend.is_available()) {
+            println!("{:<16} 利用不可（このCPUでは未対応）", backend.name());
+        }
+        println!();
+    }
+    
+    let mut harness = Harness::new(options.config.clone());
+    for &name in &options.backends {
+        let backend = backend_by_name(name);
+    
+        // 正当性の確認
+        let mut state = Sha512State::new();
+        backend.compress(&mut state, &blocks);
+        assert_eq!(state.h, expected.h, "{}: 汎用実装と結果が一致しません", name);
+    
+        let blocks = &blocks;
+        harness.register(name, options.input_size, move || {
+            backend.compress(black_box(&mut state), black_box(blocks));
+        });
+    }
+    report(options, &harness.run());
+}
+
+/// update ベンチマークで計測するバッファサイズ（バイト、--size で最大のサイズを変更できる）
+const UPDATE_SIZES: [usize; 3] = [64 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+
+/// --quick を指定した場合の update ベンチマークの最大のバッファサイズ
+const UPDATE_QUICK_MAX_SIZE: usize = 1024 * 1024;
+
+/// update ベンチマークの試行回数
+const UPDATE_TRIALS: usize = 10;
+
+/// 大きなバッファを `Sha512::update` に1回で渡したときのスループットを、
+/// 変更前の方式（ブロックごとにスタック上の配列へコピーし、1ブロックずつ圧縮関数を呼ぶ）と比較
+/// 結果の名前は「バックエンド/方式/バッファサイズ」です。
+fn bench_update(options: &BenchOptions) {
+    let mut results = Vec::new();
+    for size in sizes_up_to(&UPDATE_SIZES, options.input_size) {
+        let data: Vec<u8> = (0..size).map(|i| (i * 7 + 3) as u8).collect();
+        let data = &data;
+    
+        let mut harness = Harness::new(options.config.clone());
+        for &name in &options.backends {
+            let backend = backend_by_name(name);
+    
+            // 変更前の update と同じ処理
+            let per_block = move |data: &[u8]| {
+                let mut state = Sha512State::new();
+                for chunk in data.chunks_exact(128) {
+                    let mut block = [0u8; 128];
+                    block.copy_from_slice(chunk);
+                    backend.compress(&mut state, &[block]);
+                }
+                state
+            };
+            // 変更後の update と同じ処理（連続するブロックを入力スライスから直接渡す）
+            let multi_block = move |data: &[u8]| {
+                let mut state = Sha512State::new();
+                backend.compress(&mut state, data.as_chunks::<128>().0);
+                state
+            };
+    
+            // 正当性の確認
+            assert_eq!(per_block(data).h, multi_block(data).h, "2つの方式の結果が一致しません");
+    
+            harness.register(format!("{}/per-block-copy/{}", name, size), size, move || {
+                black_box(per_block(black_box(data)));
+            });
+            harness.register(format!("{}/multi-block/{}", name, size), size, move || {
+                black_box(multi_block(black_box(data)));
+            });
+            harness.register(format!("{}/Sha512::update/{}", name, size), size, move || {
+                let mut hasher = Sha512::with_backend(backend).unwrap();
+                hasher.update(black_box(data));
+                black_box(hasher.finalize());
+            });
+        }
+        results.extend(harness.run());
+    }
+    report(options, &results);
+}
+
+/// サイズ別ベンチマークで計測するメッセージ長（バイト）
+/// 短いメッセージ（SHA-256 と共通の 0, 1, 55, 56, 64 に加え、SHA-512 のパディング境界である
+/// 111, 112 とブロック長の 128。112 バイト以上で2ブロックになる）と、大きなメッセージでの定常的な
+/// スループットを確認する長さ（--size で最大のメッセージ長を変更できる）
+const SWEEP_SIZES: [usize; 12] = [
+    0, 1, 55, 56, 64, 111, 112, 128, 1024, 16 * 1024, 1024 * 1024, 64 * 1024 * 1024,
+];
+
+/// サイズ別ベンチマークの1サンプルあたりの目安のバイト数（短いメッセージは複数回まとめて計測する）
+const SWEEP_BYTES_PER_SAMPLE: usize = 1024 * 1024;
+
+/// サイズ別ベンチマークのサンプル数
+const SWEEP_SAMPLES: usize = 7;
+
+/// --quick を指定した場合のサイズ別ベンチマークの1サンプルあたりの目安のバイト数
+const SWEEP_QUICK_BYTES_PER_SAMPLE: usize = 64 * 1024;
+
+/// --quick を指定した場合のサイズ別ベンチマークの最大のメッセージ長
+const SWEEP_QUICK_MAX_SIZE: usize = 1024 * 1024;
+
+/// サイズ別ベンチマークの既定の設定（回数は128バイトのメッセージでの値）
+fn sweep_config(bytes_per_sample: usize, samples: usize) -> BenchConfig {
+    let calls = bytes_per_sample / 128;
+    BenchConfig { warmup: Warmup::Iterations(calls), samples, iterations_per_sample: calls }
+}
+
+/// 128バイトのメッセージを基準にした回数を `size` バイトのメッセージ用に減らす
+/// （1サンプルが短くなりすぎないよう、短いメッセージは複数回まとめて計測する）
+fn scale_config(config: &BenchConfig, size: usize) -> BenchConfig {
+    let scale = |n: usize| if n == 0 { 0 } else { (n.saturating_mul(128) / size.max(128)).max(1) };
+    BenchConfig {
+        warmup: match config.warmup {
+            Warmup::Iterations(n) => Warmup::Iterations(scale(n)),
+            warmup => warmup,
+        },
+        samples: config.samples,
+        iterations_per_sample: scale(config.iterations_per_sample),
+    }
+}
+
+/// 長さの異なるメッセージを `Sha512` の update / finalize で端から端まで計算し、
+/// 選択したバックエンド（既定は利用可能なすべて）についてメッセージ長ごとに計測
+/// 結果の名前は「バックエンド/メッセージ長」で、従来の表示の場合はメッセージあたりの時間と
+/// スループットの一覧を併せて表示します。
+fn bench_sweep(options: &BenchOptions) {
+    let sizes = sizes_up_to(&SWEEP_SIZES, options.input_size);
+    let data: Vec<u8> = (0..options.input_size).map(|i| (i * 7 + 3) as u8).collect();
+    
+    let mut results = Vec::new();
+    for &size in &sizes {
+        let message = &data[..size];
+        let config = scale_config(&options.config, size);
+        for &name in &options.backends {
+            let backend = backend_by_name(name);
+            results.push(run_bench(&format!("{}/{}", name, size), size, &config, || {
+                let mut hasher = Sha512::with_backend(backend).unwrap();
+                hasher.update(black_box(message));
+                black_box(hasher.finalize());
+            }));
+        }
+    }
+    report(options, &results);
     
-    test_custom_values();
+    if options.format == OutputFormat::Human {
+        println!();
+        println!("=== 1メッセージあたりの時間（中央値基準） ===");
+        for result in &results {
+            let ns_per_message = median_call_ns(result);
+            let mb_per_sec = if result.bytes_per_iteration == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.1}", result.bytes_per_iteration as f64 / ns_per_message * 1e3)
+            };
+            println!("{:<28} {:>14.1} ns/メッセージ {:>10} MB/秒", result.name, ns_per_message, mb_per_sec);
+        }
+    }
+}
+
+/// 一括計算ベンチマークのメッセージ数
+const MANY_MESSAGES: usize = 100_000;
+
+/// 一括計算ベンチマークのメッセージ長の上限（バイト）
+const MANY_MAX_LEN: usize = 256;
+
+/// 一括計算ベンチマークの試行回数
+const MANY_TRIALS: usize = 10;
+
+/// 長さの異なる多数の短いメッセージについて、選択したバックエンドの `Sha512` を1本ずつ呼び出す場合
+/// （結果の名前は "serial/バックエンド"）と、`hash_many` でまとめて計算する場合の速度を比較
+/// 処理バイト数は全メッセージの合計で、従来の表示の場合は1秒あたりのメッセージ数を併せて表示します。
+fn bench_many(options: &BenchOptions) {
+    // 0〜MANY_MAX_LEN バイトの擬似乱数長のメッセージを生成
+    let mut seed = 0x2545_f491u32;
+    let messages: Vec<Vec<u8>> = (0..MANY_MESSAGES)
+        .map(|n| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let len = (seed >> 8) as usize % (MANY_MAX_LEN + 1);
+            (0..len).map(|i| (i + n) as u8).collect()
+        })
+        .collect();
+    let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+    let refs = &refs;
+    let total_bytes: usize = messages.iter().map(Vec::len).sum();
+    
+    let serial = |backend: &'static dyn BlockCompressor, refs: &[&[u8]]| -> Vec<[u8; 64]> {
+        refs.iter()
+            .map(|message| {
+                let mut hasher = Sha512::with_backend(backend).unwrap();
+                hasher.update(message);
+                hasher.finalize()
+            })
+            .collect()
+    };
+    
+    // 正当性の確認
+    let expected = serial(best_backend(), refs);
+    assert!(expected == hash_many(refs), "hash_many の結果が逐次計算と一致しません");
+    if ManyBackend::Avx2.is_available() {
+        assert!(
+            Some(&expected) == hash_many_with(ManyBackend::Avx2, refs).as_ref(),
+            "AVX2の4レーン処理の結果が逐次計算と一致しません"
+        );
+    }
+    for &name in &options.backends {
+        assert!(serial(backend_by_name(name), refs) == expected, "{}: 逐次計算の結果が一致しません", name);
+    }
+    
+    if options.format == OutputFormat::Human {
+        println!("メッセージ数: {}（0〜{}バイト、合計 {} バイト）", MANY_MESSAGES, MANY_MAX_LEN, total_bytes);
+        println!("hash_many の実装: {}\n", ManyBackend::best().name());
+    }
+    
+    let mut harness = Harness::new(options.config.clone());
+    for &name in &options.backends {
+        let backend = backend_by_name(name);
+        harness.register(format!("serial/{}", name), total_bytes, move || {
+            black_box(serial(backend, black_box(refs)));
+        });
+    }
+    harness.register(format!("hash_many/{}", ManyBackend::best().name()), total_bytes, || {
+        black_box(hash_many(black_box(refs)));
+    });
+    let results = harness.run();
+    report(options, &results);
+    
+    if options.format == OutputFormat::Human {
+        println!();
+        println!("=== 1秒あたりのメッセージ数（中央値基準） ===");
+        for result in &results {
+            println!(
+                "{:<28} {:>12.0} メッセージ/秒",
+                result.name,
+                MANY_MESSAGES as f64 / (median_call_ns(result) / 1e9)
+            );
+        }
+    }
 }
 
 fn print_state(label: &str, state: &Sha512State) {
@@ -20,20 +484,14 @@ fn print_state(label: &str, state: &Sha512State) {
 }
 
 
-fn test_custom_values() {
-    
-    // 初期状態
-    let initial_state = Sha512State {
-        h: [
-            0x6a09e667f3bcc908,
-            0xbb67ae8584caa73b,
-            0x3c6ef372fe94f82b,
-            0xa54ff53a5f1d36f1,
-            0x510e527fade682d1,
-            0x9b05688c2b3e6c1f,
-            0x1f83d9abfb41bd6b,
-            0x5be0cd19137e2179,
-        ],
+fn test_custom_values(options: &BenchOptions) {
+    
+    // 初期状態（--algorithm で選択したアルゴリズムのIV）
+    let initial_state = match options.algorithm {
+        "sha384" => Sha512State::new_384(),
+        "sha512/224" => Sha512State::new_512_224(),
+        "sha512/256" => Sha512State::new_512_256(),
+        _ => Sha512State::new(),
     };
     
     // テスト用の128バイトデータブロック (メッセージ "abc" をパディングしたもの)
@@ -57,101 +515,47 @@ fn test_custom_values() {
         0x00, 0x00, 0x00, 0x18,
     ];
     
-    // 初期状態表示
-    print_state("初期状態", &initial_state);
-    println!();
-    
-    // 動作確認のため、最初の1回だけ実行して結果を表示
-    let mut state = initial_state.clone();
-    let start = Instant::now();
-    sha512_transform_generic(black_box(&mut state), black_box(&block));  // data → block に修正
-    let duration = start.elapsed();
-    
-    print_state("最終状態", &state);
-    println!();
-    println!("実行時間: {:.10}秒\n", duration.as_secs_f64());
-    
-
-const ITERATIONS: usize = 10_000_000;
-
-// 空回し (ウォームアップ) ---
-println!("CPUウォームアップ中({}回)...", ITERATIONS);
-let mut warmup_state = initial_state.clone();
-for i in 0..ITERATIONS {
-    sha512_transform_generic(black_box(&mut warmup_state), black_box(&block));  // 配列を削除してblockを直接渡す
-    
-    black_box(warmup_state);
-    black_box(i);
-}
-
-// 1000万回の繰り返し測定ループ
-// 2^24 = 16_777_216 で10_000_000に近い
-const ITERATIONS_1: usize = 16384; // 2^14
-const ITERATIONS_2: usize = 1024; // 2^10
-let mut times: Vec<u128> = Vec::with_capacity(ITERATIONS_1);
-
-for i in 0..ITERATIONS_1 {
-    let mut state = initial_state.clone();
-    let start = Instant::now();
-
-    for j in 0..ITERATIONS_2 {
-         // 最適化で消されないよう black_box を介して実行
-        sha512_transform_generic(black_box(&mut state), black_box(&block));  // 配列を削除してblockを直接渡す
+    // 従来の表示（既定）の場合のみ、動作確認の結果と計測の条件を表示する
+    // （JSON などの場合は標準出力に計測結果だけを出力する）
+    let human = options.format == OutputFormat::Human;
+    if human {
+        print_state("初期状態", &initial_state);
+        println!();
         
-        black_box(j);// ループ変数を black_box に入れることでループ自体の最適化を抑制
+        // 動作確認のため、最初の1回だけ実行して結果を表示
+        let mut state = initial_state;
+        let start = Instant::now();
+        // コンパイラによる最適化削除を防ぎつつ実行
+        sha512_transform_generic(black_box(&mut state), black_box(&block));
+        let duration = start.elapsed();
+        
+        print_state("最終状態", &state);
+        println!();
+        println!("実行時間: {:.10}秒", duration.as_secs_f64());
+        println!(
+            "アルゴリズム: {} / 入力: {}バイト / バックエンド: {}",
+            options.algorithm,
+            options.input_size,
+            options.backends.join(", ")
+        );
     }
-
-    let elapsed = start.elapsed().as_nanos();
-    times.push(elapsed);
-    black_box(i);
-}
-
-// 統計計算用の関数を追加
-fn calculate_stats(times: &[u128]) -> (f64, f64, f64) {
-    let n = times.len() as f64;
-    
-    // 平均値
-    let sum: u128 = times.iter().sum();
-    let mean = sum as f64 / n;
-    
-    // 中央値
-    let mut sorted = times.to_vec();
-    sorted.sort_unstable();
-    let median = if sorted.len() % 2 == 0 {
-        let mid = sorted.len() / 2;
-        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
-    } else {
-        sorted[sorted.len() / 2] as f64
-    };
     
-    // 標準偏差
-    let variance: f64 = times.iter()
-        .map(|&x| {
-            let diff = x as f64 - mean;
-            diff * diff
-        })
-        .sum::<f64>() / n;
-    let std_dev = variance.sqrt();
+    // 計測する入力: "abc" のブロックを --size で指定したバイト数まで繰り返したもの
+    let data = vec![block; options.input_size / 128];
     
-    (mean, median, std_dev)
+    // 空回し (ウォームアップ) と繰り返し測定は共通ハーネスで行う
+    if human {
+        println!("CPUウォームアップ中({})...", options.config.warmup);
+    }
+    let mut harness = Harness::new(options.config.clone());
+    for &name in &options.backends {
+        let backend = backend_by_name(name);
+        let mut state = initial_state;
+        let data = &data;
+        harness.register(name, options.input_size, move || {
+            // 最適化で消されないよう black_box を介して実行
+            backend.compress(black_box(&mut state), black_box(data));
+        });
+    }
+    report(options, &harness.run());
 }
-
-// 元のループの後を以下に置き換え
-let (mean_ns, median_ns, std_dev_ns) = calculate_stats(&times);
-
-println!("=== 統計情報（{}回の処理あたり） ===", ITERATIONS_2);
-println!("平均値:   {:.12} 秒", mean_ns / 1e9);
-println!("中央値:   {:.12} 秒", median_ns / 1e9);
-println!("標準偏差: {:.12} 秒", std_dev_ns / 1e9);
-println!();
-
-// 全体の合計をu128で計算してからf64に変換
-let total_ns: u128 = times.iter().sum();
-let total_calls: u128 = (ITERATIONS_1 * ITERATIONS_2) as u128;  // 2^24 = 16777216
-let avg_ns = total_ns as f64 / total_calls as f64;
-let avg_sec = avg_ns / 1e9;
-
-println!("=== 1回あたりの平均実行時間 ===");
-println!("{:.12} 秒", avg_sec);
-
-}
\ No newline at end of file
@@ -0,0 +1,288 @@
+// 多数の独立したメッセージをまとめてハッシュするAPI
+//
+// AVX2が利用できる場合は、4本のメッセージを1本ずつレーンに割り当てて同時に圧縮します。
+// メッセージごとにパディング後のブロック数が異なるため、処理を終えたレーンには
+// 次のメッセージを順次割り当て、空きレーンが残らないようにしています。
+// どちらの実装も `hash_many_with` で明示的に選択できます。
+
+// レーン処理はAVX2を持つx86でのみ使用する（その他の環境ではテストからのみ使用）
+#![cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+
+use crate::{Sha512, Sha512State};
+
+/// `hash_many_with` で使用する実装
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManyBackend {
+    /// 各メッセージを `Sha512`（自動選択されたバックエンド）で順番に計算する（すべての環境で利用可能）
+    Serial,
+    /// AVX2で4本のメッセージを同時に圧縮する（AVX2を持つx86のみ）
+    Avx2,
+}
+
+impl ManyBackend {
+    /// ベンチマーク結果などに表示する名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManyBackend::Serial => "serial",
+            ManyBackend::Avx2 => "avx2-4way",
+        }
+    }
+
+    /// 実行中のCPUでこの実装が利用できるかどうか
+    pub fn is_available(&self) -> bool {
+        match self {
+            ManyBackend::Serial => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            ManyBackend::Avx2 => crate::avx2::is_supported(),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            ManyBackend::Avx2 => false,
+        }
+    }
+
+    /// `hash_many` が使用する実装（AVX2があれば4レーン）
+    pub fn best() -> ManyBackend {
+        if ManyBackend::Avx2.is_available() {
+            ManyBackend::Avx2
+        } else {
+            ManyBackend::Serial
+        }
+    }
+}
+
+/// 複数のメッセージのSHA-512をまとめて計算する（結果は入力と同じ順序）
+///
+/// 実装は `ManyBackend::best` で選択します。AVX2が利用できない環境では、
+/// 各メッセージを `Sha512` で順番に計算します。
+pub fn hash_many(messages: &[&[u8]]) -> Vec<[u8; 64]> {
+    hash_many_with(ManyBackend::best(), messages).expect("選択した実装は常に利用できる")
+}
+
+/// 指定した実装で複数のメッセージのSHA-512をまとめて計算する
+/// 実行中のCPUで `backend` が利用できない場合は `None` を返します。
+pub fn hash_many_with(backend: ManyBackend, messages: &[&[u8]]) -> Option<Vec<[u8; 64]>> {
+    if !backend.is_available() {
+        return None;
+    }
+    match backend {
+        ManyBackend::Serial => Some(
+            messages
+                .iter()
+                .map(|message| {
+                    let mut hasher = Sha512::new();
+                    hasher.update(message);
+                    hasher.finalize()
+                })
+                .collect(),
+        ),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        ManyBackend::Avx2 => {
+            // SAFETY: AVX2が利用できることを直前に確認している
+            Some(hash_lanes(messages, |states, blocks| unsafe {
+                crate::avx2::transform4(states, blocks)
+            }))
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        ManyBackend::Avx2 => None,
+    }
+}
+
+/// 同時に処理するメッセージ数（AVX2の4レーンに合わせる）
+pub(crate) const LANES: usize = 4;
+
+/// パディングを含めたブロック数（0x80 の1バイトと長さ情報の16バイトが必要）
+fn padded_blocks(len: usize) -> usize {
+    (len + 17).div_ceil(128)
+}
+
+/// パディング済みメッセージの `index` 番目のブロックを組み立てる
+fn padded_block(data: &[u8], index: usize, block: &mut [u8; 128]) {
+    let start = index * 128;
+    block.fill(0);
+
+    if start < data.len() {
+        let n = (data.len() - start).min(128);
+        block[..n].copy_from_slice(&data[start..start + n]);
+    }
+    // メッセージ末尾の直後に 0x80 を置く
+    if (start..start + 128).contains(&data.len()) {
+        block[data.len() - start] = 0x80;
+    }
+    // 最終ブロックの末尾16バイトに総ビット長（128ビット）を書き込む
+    if index + 1 == padded_blocks(data.len()) {
+        block[112..].copy_from_slice(&(data.len() as u128 * 8).to_be_bytes());
+    }
+}
+
+/// 各レーンで処理中のメッセージ
+struct Lane {
+    message: usize,   // 入力中の位置（結果の格納先）
+    block: usize,     // 次に処理するブロック番号
+}
+
+/// 4レーンの圧縮関数 `transform` を使って全メッセージを処理する
+fn hash_lanes<F>(messages: &[&[u8]], mut transform: F) -> Vec<[u8; 64]>
+where
+    F: FnMut(&mut [[u64; 8]; LANES], &[[u8; 128]; LANES]),
+{
+    let mut digests = vec![[0u8; 64]; messages.len()];
+    let mut states = [[0u64; 8]; LANES];
+    let mut blocks = [[0u8; 128]; LANES];
+    let mut lanes: [Option<Lane>; LANES] = Default::default();
+    let mut next = 0;
+
+    loop {
+        // 空いているレーンに次のメッセージを割り当てる
+        for (lane, state) in lanes.iter_mut().zip(states.iter_mut()) {
+            if lane.is_none() && next < messages.len() {
+                *lane = Some(Lane { message: next, block: 0 });
+                *state = Sha512State::new().h;
+                next += 1;
+            }
+        }
+        if lanes.iter().all(Option::is_none) {
+            break;
+        }
+
+        // 各レーンの次のブロックを用意する（空きレーンは結果を使わないため内容は任意）
+        for (lane, block) in lanes.iter().zip(blocks.iter_mut()) {
+            if let Some(lane) = lane {
+                padded_block(messages[lane.message], lane.block, block);
+            }
+        }
+        transform(&mut states, &blocks);
+
+        // 最終ブロックまで処理したレーンの結果を書き出して空ける
+        for (slot, state) in lanes.iter_mut().zip(states.iter()) {
+            let Some(lane) = slot else { continue };
+            lane.block += 1;
+            if lane.block == padded_blocks(messages[lane.message].len()) {
+                for (i, word) in state.iter().enumerate() {
+                    digests[lane.message][i * 8..(i + 1) * 8].copy_from_slice(&word.to_be_bytes());
+                }
+                *slot = None;
+            }
+        }
+    }
+    digests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha512_transform_generic;
+
+    /// 汎用実装で4レーン分を順に処理する、テスト用の圧縮関数
+    fn transform4_generic(states: &mut [[u64; 8]; LANES], blocks: &[[u8; 128]; LANES]) {
+        for (state, block) in states.iter_mut().zip(blocks) {
+            let mut s = Sha512State { h: *state };
+            sha512_transform_generic(&mut s, block);
+            *state = s.h;
+        }
+    }
+
+    fn serial(messages: &[&[u8]]) -> Vec<[u8; 64]> {
+        messages
+            .iter()
+            .map(|message| {
+                let mut hasher = Sha512::new();
+                hasher.update(message);
+                hasher.finalize()
+            })
+            .collect()
+    }
+
+    /// 簡易な線形合同法による擬似乱数列
+    fn lcg(seed: &mut u32) -> u32 {
+        *seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        *seed >> 8
+    }
+
+    /// パディングの境界をまたぐ長さを含む、長さ・内容がランダムなメッセージの集合
+    fn random_messages(seed: u32, count: usize) -> Vec<Vec<u8>> {
+        let mut seed = seed;
+        let mut lengths = vec![0, 1, 3, 111, 112, 113, 127, 128, 129, 239, 240, 255, 256, 2000];
+        lengths.extend((0..count).map(|_| lcg(&mut seed) as usize % 600));
+        lengths
+            .iter()
+            .map(|&len| (0..len).map(|_| lcg(&mut seed) as u8).collect())
+            .collect()
+    }
+
+    // パディング済みブロックが Sha512 と同じ内部状態を与えること
+    #[test]
+    fn test_lane_padding() {
+        let messages = random_messages(1, 50);
+        let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        assert_eq!(hash_lanes(&refs, transform4_generic), serial(&refs));
+    }
+
+    // hash_many（実行環境で選択される実装）が複数のランダムなメッセージ集合で逐次計算と一致すること
+    #[test]
+    fn test_hash_many_matches_serial() {
+        for seed in 0..8 {
+            let messages = random_messages(seed, seed as usize * 7);
+            for count in [0, 1, 3, 4, 5, messages.len()] {
+                let refs: Vec<&[u8]> = messages[..count].iter().map(Vec::as_slice).collect();
+                assert_eq!(hash_many(&refs), serial(&refs), "seed {}, {} messages", seed, count);
+            }
+        }
+    }
+
+    // hash_many を経由せずに AVX2 の4レーン処理を直接呼び出し、逐次計算と一致すること（非対応CPUでは省略）
+    #[test]
+    fn test_hash_many_with_avx2() {
+        if !ManyBackend::Avx2.is_available() {
+            eprintln!("AVX2 に非対応のため省略");
+            assert_eq!(hash_many_with(ManyBackend::Avx2, &[b"abc"]), None);
+            return;
+        }
+        for seed in 0..8 {
+            let messages = random_messages(seed, seed as usize * 7);
+            let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+            assert_eq!(hash_many_with(ManyBackend::Avx2, &refs), Some(serial(&refs)), "seed {}", seed);
+        }
+    }
+
+    // 逐次計算の実装はすべての環境で利用でき、hash_many の実装も利用可能であること
+    #[test]
+    fn test_hash_many_with_serial() {
+        let messages = random_messages(3, 20);
+        let refs: Vec<&[u8]> = messages.iter().map(Vec::as_slice).collect();
+        assert_eq!(hash_many_with(ManyBackend::Serial, &refs), Some(serial(&refs)));
+        assert!(ManyBackend::best().is_available());
+    }
+
+    // "abc" の既知のハッシュ値
+    #[test]
+    fn test_hash_many_abc() {
+        let digests = hash_many(&[b"abc", b""]);
+        assert_eq!(digests[0][..4], [0xdd, 0xaf, 0x35, 0xa1]);
+        assert_eq!(digests[1][..4], [0xcf, 0x83, 0xe1, 0x35]);
+    }
+
+    // AVX2実装を sha512_transform_generic と直接比較する（非対応CPUでは省略）
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_transform4_matches_generic() {
+        if !crate::avx2::is_supported() {
+            return;
+        }
+        let mut seed = 7;
+        for _ in 0..100 {
+            let mut blocks = [[0u8; 128]; LANES];
+            let mut states = [[0u64; 8]; LANES];
+            for lane in 0..LANES {
+                blocks[lane] = std::array::from_fn(|_| lcg(&mut seed) as u8);
+                states[lane] = std::array::from_fn(|_| {
+                    (lcg(&mut seed) as u64) << 40 ^ (lcg(&mut seed) as u64) << 16 ^ lcg(&mut seed) as u64
+                });
+            }
+
+            let mut expected = states;
+            transform4_generic(&mut expected, &blocks);
+            // SAFETY: AVX2が利用できることを確認済み
+            unsafe { crate::avx2::transform4(&mut states, &blocks) };
+            assert_eq!(states, expected);
+        }
+    }
+}
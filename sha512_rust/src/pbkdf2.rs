@@ -0,0 +1,135 @@
+// PBKDF2-HMAC-SHA512（RFC 8018 §5.2）によるパスワードベース鍵導出
+
+use crate::HmacSha512;
+
+/// SHA-512の出力長（hLen）
+const HASH_LEN: usize = 64;
+
+/// PBKDF2の処理で発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pbkdf2Error {
+    /// 反復回数が0
+    ZeroRounds,
+    /// 要求された出力長が (2^32 - 1) * hLen を超えている
+    InvalidLength(usize),
+}
+
+impl std::fmt::Display for Pbkdf2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pbkdf2Error::ZeroRounds => write!(f, "PBKDF2: iteration count must be at least 1"),
+            Pbkdf2Error::InvalidLength(len) => {
+                write!(f, "PBKDF2: derived key length {} is too long", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Pbkdf2Error {}
+
+/// PBKDF2-HMAC-SHA512で `password` と `salt` から `output` 全体を埋める鍵を導出
+///
+/// 出力は64バイト単位のブロック T_1, T_2, ... を連結したもので、任意の長さを指定できます。
+/// パスワードで鍵付けしたHMACの内側・外側状態は一度だけ計算し、各反復で使い回します。
+pub fn pbkdf2(
+    password: &[u8],
+    salt: &[u8],
+    rounds: u32,
+    output: &mut [u8],
+) -> Result<(), Pbkdf2Error> {
+    if rounds == 0 {
+        return Err(Pbkdf2Error::ZeroRounds);
+    }
+    if output.len() as u64 > u32::MAX as u64 * HASH_LEN as u64 {
+        return Err(Pbkdf2Error::InvalidLength(output.len()));
+    }
+
+    let prf = HmacSha512::new(password);
+
+    for (i, chunk) in output.chunks_mut(HASH_LEN).enumerate() {
+        // U_1 = PRF(P, S || INT(i))
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize();
+        let mut t = u;
+
+        // U_j = PRF(P, U_{j-1}) を反復し、T_i = U_1 ^ U_2 ^ ... ^ U_c を求める
+        for _ in 1..rounds {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for (x, y) in t.iter_mut().zip(u.iter()) {
+                *x ^= y;
+            }
+        }
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn derive(password: &[u8], salt: &[u8], rounds: u32, len: usize) -> Vec<u8> {
+        let mut output = vec![0u8; len];
+        pbkdf2(password, salt, rounds, &mut output).unwrap();
+        output
+    }
+
+    // RFC 7914 §11 の入力に対するPBKDF2-HMAC-SHA512の値
+    // （RFCにはSHA-512の期待値がないため、Python hashlib.pbkdf2_hmac で計算した値）
+    #[test]
+    fn test_pbkdf2_sha512_rfc7914_inputs() {
+        assert_eq!(
+            derive(b"passwd", b"salt", 1, 64),
+            hex("c74319d99499fc3e9013acff597c23c5baf0a0bec5634c46b8352b793e324723\
+                 d55caa76b2b25c43402dcfdc06cdcf66f95b7d0429420b39520006749c51a04e")
+        );
+        assert_eq!(
+            derive(b"Password", b"NaCl", 80000, 64),
+            hex("e6337d6fbeb645c794d4a9b5b75b7b30dac9ac50376a91df1f4460f6060d5add\
+                 b2c1fd1f84409abacc67de7eb4056e6bb06c2d82c3ef4ccd1bded0f675ed97c6")
+        );
+    }
+
+    // RFC 6070 と同じ入力に対するPBKDF2-HMAC-SHA512の値（同上）
+    #[test]
+    fn test_pbkdf2_sha512_rfc6070_inputs() {
+        assert_eq!(
+            derive(b"password", b"salt", 1, 32),
+            hex("867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252")
+        );
+        assert_eq!(
+            derive(b"password", b"salt", 2, 32),
+            hex("e1d9c16aa681708a45f5c7c4e215ceb66e011a2e9f0040713f18aefdb866d53c")
+        );
+        assert_eq!(
+            derive(b"password", b"salt", 4096, 32),
+            hex("d197b1b33db0143e018b12f3d1d1479e6cdebdcc97c5c0f87f6902e072f457b5")
+        );
+        // 出力長がブロック長の倍数でない場合
+        assert_eq!(
+            derive(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 40),
+            hex("8c0511f4c6e597c6ac6315d8f0362e225f3c501495ba23b868c005174dc4ee71115b59f9e60cd953")
+        );
+        // NULバイトを含むパスワード・ソルト
+        assert_eq!(
+            derive(b"pass\0word", b"sa\0lt", 4096, 16),
+            hex("9d9e9c4cd21fe4be24d5b8244c759665")
+        );
+    }
+
+    // 反復回数0はエラーになること
+    #[test]
+    fn test_pbkdf2_sha512_zero_rounds() {
+        let mut output = [0u8; 32];
+        assert_eq!(
+            pbkdf2(b"password", b"salt", 0, &mut output),
+            Err(Pbkdf2Error::ZeroRounds)
+        );
+    }
+}
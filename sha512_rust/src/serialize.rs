@@ -0,0 +1,361 @@
+// 途中状態のエクスポート／インポート（中断したハッシュ計算の再開用）
+//
+// バイト列のフォーマット（バージョン1、数値はすべてビッグエンディアン）:
+//
+//   オフセット  長さ  内容
+//   0           4     マジック "SHAS"
+//   4           1     フォーマットバージョン (1)
+//   5           1     アルゴリズムID (3 = SHA-512, 4 = SHA-384,
+//                     5 = SHA-512/224, 6 = SHA-512/256, 7 = SHA-512/t)
+//   6           64    連鎖値 H0〜H7（u64 × 8）
+//   70          16    これまでに入力した総バイト数（u128）
+//   86          1     バッファ内のバイト数 n（0〜127）
+//   87          n     未処理のバッファ内容
+//   87 + n      2     出力ビット長 t（u16、SHA-512/t の場合のみ）
+//
+// アルゴリズムIDはsha256_armと共通の番号空間を使用しており、
+// SHA-256系の状態を誤って読み込んだ場合もエラーとして検出できます。
+
+use crate::{
+    validate_truncation, Sha384, Sha512, Sha512State, Sha512T, Sha512_224, Sha512_256,
+    TruncationError,
+};
+
+/// フォーマット識別用のマジックバイト
+const MAGIC: &[u8; 4] = b"SHAS";
+
+/// 現在のフォーマットバージョン
+const VERSION: u8 = 1;
+
+/// アルゴリズムID
+const ALG_SHA512: u8 = 3;
+const ALG_SHA384: u8 = 4;
+const ALG_SHA512_224: u8 = 5;
+const ALG_SHA512_256: u8 = 6;
+const ALG_SHA512_T: u8 = 7;
+
+/// 固定長部分（バッファ内容を除く）のバイト数
+const HEADER_LEN: usize = 4 + 1 + 1 + 64 + 16 + 1;
+
+/// 途中状態のインポートで発生するエラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// マジックバイトが一致しない
+    InvalidMagic,
+    /// 対応していないフォーマットバージョン
+    UnsupportedVersion(u8),
+    /// 読み込み先と異なるアルゴリズムの状態
+    AlgorithmMismatch { expected: u8, found: u8 },
+    /// バイト列の長さがフォーマットと一致しない
+    InvalidLength(usize),
+    /// バッファ長と総バイト数の整合性が取れていない
+    Inconsistent,
+    /// SHA-512/t の出力ビット長 t が不正
+    InvalidTruncation(TruncationError),
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::InvalidMagic => write!(f, "hash state: invalid magic bytes"),
+            StateError::UnsupportedVersion(v) => {
+                write!(f, "hash state: unsupported format version {}", v)
+            }
+            StateError::AlgorithmMismatch { expected, found } => write!(
+                f,
+                "hash state: algorithm id {} does not match expected id {}",
+                found, expected
+            ),
+            StateError::InvalidLength(len) => write!(f, "hash state: invalid length {}", len),
+            StateError::Inconsistent => {
+                write!(f, "hash state: buffer length does not match total length")
+            }
+            StateError::InvalidTruncation(e) => write!(f, "hash state: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+impl Sha512 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.export_with(ALG_SHA512)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Self::import_with(bytes, ALG_SHA512, 0)
+    }
+
+    fn export_with(&self, algorithm: u8) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.buffer_len);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(algorithm);
+        for word in &self.state.h {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+        out.extend_from_slice(&self.total_len.to_be_bytes());
+        out.push(self.buffer_len as u8);
+        out.extend_from_slice(&self.buffer[..self.buffer_len]);
+        out
+    }
+
+    /// `extra` はバッファ内容の後に続くアルゴリズム固有のフィールドのバイト数
+    fn import_with(bytes: &[u8], algorithm: u8, extra: usize) -> Result<Self, StateError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(StateError::InvalidLength(bytes.len()));
+        }
+        if &bytes[0..4] != MAGIC {
+            return Err(StateError::InvalidMagic);
+        }
+        if bytes[4] != VERSION {
+            return Err(StateError::UnsupportedVersion(bytes[4]));
+        }
+        if bytes[5] != algorithm {
+            return Err(StateError::AlgorithmMismatch {
+                expected: algorithm,
+                found: bytes[5],
+            });
+        }
+
+        let buffer_len = bytes[86] as usize;
+        if buffer_len >= 128 {
+            return Err(StateError::Inconsistent);
+        }
+        if bytes.len() != HEADER_LEN + buffer_len + extra {
+            return Err(StateError::InvalidLength(bytes.len()));
+        }
+
+        let mut state = Sha512State { h: [0; 8] };
+        for (i, word) in state.h.iter_mut().enumerate() {
+            let offset = 6 + i * 8;
+            *word = u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        }
+        let total_len = u128::from_be_bytes(bytes[70..86].try_into().unwrap());
+
+        // バッファには常に「総バイト数 mod 128」バイトが残っているはず
+        if total_len % 128 != buffer_len as u128 {
+            return Err(StateError::Inconsistent);
+        }
+
+        let mut hasher = Sha512::with_state(state);
+        hasher.buffer[..buffer_len].copy_from_slice(&bytes[HEADER_LEN..HEADER_LEN + buffer_len]);
+        hasher.buffer_len = buffer_len;
+        hasher.total_len = total_len;
+        Ok(hasher)
+    }
+}
+
+impl Sha384 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.inner.export_with(ALG_SHA384)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Ok(Self {
+            inner: Sha512::import_with(bytes, ALG_SHA384, 0)?,
+        })
+    }
+}
+
+impl Sha512_224 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.inner.export_with(ALG_SHA512_224)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Ok(Self {
+            inner: Sha512::import_with(bytes, ALG_SHA512_224, 0)?,
+        })
+    }
+}
+
+impl Sha512_256 {
+    /// 現在の途中状態を自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        self.inner.export_with(ALG_SHA512_256)
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        Ok(Self {
+            inner: Sha512::import_with(bytes, ALG_SHA512_256, 0)?,
+        })
+    }
+}
+
+impl Sha512T {
+    /// 現在の途中状態を出力ビット長 t とともに自己記述的なバイト列として書き出す
+    pub fn export_state(&self) -> Vec<u8> {
+        let mut out = self.inner.export_with(ALG_SHA512_T);
+        out.extend_from_slice(&(self.t as u16).to_be_bytes());
+        out
+    }
+
+    /// `export_state` で書き出したバイト列から途中状態を復元
+    /// 記録された t が SHA-512/t で許可されていない値の場合はエラーになります。
+    pub fn import_state(bytes: &[u8]) -> Result<Self, StateError> {
+        let inner = Sha512::import_with(bytes, ALG_SHA512_T, 2)?;
+        let t = u16::from_be_bytes(bytes[bytes.len() - 2..].try_into().unwrap()) as usize;
+        validate_truncation(t).map_err(StateError::InvalidTruncation)?;
+        Ok(Self { inner, t })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 様々な位置で中断・再開しても、一括で計算した場合と同じハッシュ値になること
+    #[test]
+    fn test_sha512_resume_round_trip() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 3) as u8).collect();
+
+        let mut whole = Sha512::new();
+        whole.update(&data);
+        let expected = whole.finalize();
+
+        for split in [0, 1, 111, 112, 127, 128, 129, 256, 999, 1000] {
+            let mut first = Sha512::new();
+            first.update(&data[..split]);
+            let exported = first.export_state();
+
+            let mut resumed = Sha512::import_state(&exported).unwrap();
+            resumed.update(&data[split..]);
+            assert_eq!(resumed.finalize(), expected, "split = {}", split);
+        }
+    }
+
+    // 切り詰め出力の派生アルゴリズムも同様に再開できること
+    #[test]
+    fn test_truncated_variants_resume_round_trip() {
+        let data = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+        let mut whole = Sha384::new();
+        whole.update(data);
+        let mut first = Sha384::new();
+        first.update(&data[..50]);
+        let mut resumed = Sha384::import_state(&first.export_state()).unwrap();
+        resumed.update(&data[50..]);
+        assert_eq!(resumed.finalize(), whole.finalize());
+
+        let mut whole = Sha512_224::new();
+        whole.update(data);
+        let mut first = Sha512_224::new();
+        first.update(&data[..50]);
+        let mut resumed = Sha512_224::import_state(&first.export_state()).unwrap();
+        resumed.update(&data[50..]);
+        assert_eq!(resumed.finalize(), whole.finalize());
+
+        let mut whole = Sha512_256::new();
+        whole.update(data);
+        let mut first = Sha512_256::new();
+        first.update(&data[..50]);
+        let mut resumed = Sha512_256::import_state(&first.export_state()).unwrap();
+        resumed.update(&data[50..]);
+        assert_eq!(resumed.finalize(), whole.finalize());
+    }
+
+    // SHA-512/t は出力ビット長 t も含めて再開できること
+    #[test]
+    fn test_sha512_t_resume_round_trip() {
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 7 + 3) as u8).collect();
+
+        for t in [1, 8, 100, 224, 256, 511] {
+            let mut whole = Sha512T::new(t).unwrap();
+            whole.update(&data);
+
+            let mut first = Sha512T::new(t).unwrap();
+            first.update(&data[..129]);
+            let mut resumed = Sha512T::import_state(&first.export_state()).unwrap();
+            assert_eq!(resumed.bits(), t);
+            resumed.update(&data[129..]);
+            assert_eq!(resumed.finalize(), whole.finalize(), "t = {}", t);
+        }
+    }
+
+    // SHA-512/t の状態は ID と t の両方が検査されること
+    #[test]
+    fn test_sha512_t_import_validation() {
+        let mut hasher = Sha512T::new(200).unwrap();
+        hasher.update(b"hello world");
+        let exported = hasher.export_state();
+
+        assert_eq!(
+            Sha512::import_state(&exported).err(),
+            Some(StateError::AlgorithmMismatch { expected: ALG_SHA512, found: ALG_SHA512_T })
+        );
+        let plain = Sha512::new().export_state();
+        assert_eq!(
+            Sha512T::import_state(&plain).err(),
+            Some(StateError::AlgorithmMismatch { expected: ALG_SHA512_T, found: ALG_SHA512 })
+        );
+
+        // t が欠けている場合
+        let truncated = &exported[..exported.len() - 2];
+        assert_eq!(
+            Sha512T::import_state(truncated).err(),
+            Some(StateError::InvalidLength(truncated.len()))
+        );
+
+        // 許可されていない t
+        for (t, expected) in [
+            (0u16, TruncationError::Zero),
+            (384, TruncationError::Reserved384),
+            (512, TruncationError::TooLarge(512)),
+        ] {
+            let mut bad = exported.clone();
+            let len = bad.len();
+            bad[len - 2..].copy_from_slice(&t.to_be_bytes());
+            assert_eq!(
+                Sha512T::import_state(&bad).err(),
+                Some(StateError::InvalidTruncation(expected))
+            );
+        }
+    }
+
+    // 不正なバイト列は型付きエラーとして拒否されること
+    #[test]
+    fn test_import_state_validation() {
+        let mut hasher = Sha512::new();
+        hasher.update(b"hello world");
+        let exported = hasher.export_state();
+
+        // 異なるアルゴリズムとして読み込もうとした場合
+        assert_eq!(
+            Sha384::import_state(&exported).err(),
+            Some(StateError::AlgorithmMismatch { expected: ALG_SHA384, found: ALG_SHA512 })
+        );
+
+        let mut bad_magic = exported.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(Sha512::import_state(&bad_magic).err(), Some(StateError::InvalidMagic));
+
+        let mut bad_version = exported.clone();
+        bad_version[4] = 2;
+        assert_eq!(
+            Sha512::import_state(&bad_version).err(),
+            Some(StateError::UnsupportedVersion(2))
+        );
+
+        let truncated = &exported[..exported.len() - 1];
+        assert_eq!(
+            Sha512::import_state(truncated).err(),
+            Some(StateError::InvalidLength(truncated.len()))
+        );
+
+        // 総バイト数とバッファ長の不整合
+        let mut inconsistent = exported.clone();
+        inconsistent[85] ^= 1;
+        assert_eq!(
+            Sha512::import_state(&inconsistent).err(),
+            Some(StateError::Inconsistent)
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// SSSE3 / AVX によるメッセージスケジュールのベクトル化（x86向け）
+//
+// Intel の "sha512-avx" 実装と同じ方針で、W[16..79] を128ビットレジスタで2ワードずつ計算し、
+// W[t] + K[t] を求めます。ラウンド処理はスカラーで、unrolled バックエンドと同じ展開済み・
+// レジスタリネーミングの形で行い、次の8ワード分のスケジュール計算をラウンドと並行して実行させます。
+// SHA-512 では σ1 が参照する W[t-2], W[t-1] が直前のレジスタにそろっているため、
+// SHA-256 のようにレジスタ内で前半・後半を分けて計算する必要はありません。
+// 同じコードを SSSE3 と AVX（VEX符号化）の2通りでコンパイルし、実行時に使える方を選択します。
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::backend::BlockCompressor;
+use crate::unrolled::round;
+use crate::{Sha512State, K};
+
+/// 2レーンの64ビット右ローテート
+macro_rules! rotr {
+    ($x:expr, $n:literal) => {
+        _mm_or_si128(_mm_srli_epi64::<$n>($x), _mm_slli_epi64::<{ 64 - $n }>($x))
+    };
+}
+
+/// 2ワードずつ並べた直近の16ワードの W（x0 = W[t-16..t-15] 〜 x7 = W[t-2..t-1]）から W[t..t+2] を計算する
+macro_rules! schedule {
+    ($x0:expr, $x1:expr, $x4:expr, $x5:expr, $x7:expr) => {{
+        // W[t-15..t-14] と W[t-7..t-6]
+        let w15 = _mm_alignr_epi8::<8>($x1, $x0);
+        let w7 = _mm_alignr_epi8::<8>($x5, $x4);
+        let s0 = _mm_xor_si128(
+            _mm_xor_si128(rotr!(w15, 1), rotr!(w15, 8)),
+            _mm_srli_epi64::<7>(w15),
+        );
+        let w2 = $x7;
+        let s1 = _mm_xor_si128(
+            _mm_xor_si128(rotr!(w2, 19), rotr!(w2, 61)),
+            _mm_srli_epi64::<6>(w2),
+        );
+        _mm_add_epi64(_mm_add_epi64($x0, s0), _mm_add_epi64(w7, s1))
+    }};
+}
+
+/// 有効にする命令セット拡張ごとに圧縮関数を生成する
+macro_rules! compress_fn {
+    ($name:ident, $feature:literal) => {
+        /// SIMDでW+Kを求め、展開済みのスカラーのラウンドで処理する圧縮関数
+        #[target_feature(enable = $feature)]
+        unsafe fn $name(state: &mut Sha512State, blocks: &[[u8; 128]]) {
+            // 各64ビットワードをビッグエンディアンとして読み込むためのバイト並べ替えマスク
+            let mask = _mm_set_epi64x(
+                0x0809_0a0b_0c0d_0e0f_u64 as i64,
+                0x0001_0203_0405_0607_u64 as i64,
+            );
+
+            for block in blocks {
+                // 直近の16ワードの W（2ワードずつ、W[t] は w[(t / 2) % 8] に入る）
+                let data_ptr = block.as_ptr() as *const __m128i;
+                let mut w = [
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(1)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(2)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(3)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(4)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(5)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(6)), mask),
+                    _mm_shuffle_epi8(_mm_loadu_si128(data_ptr.add(7)), mask),
+                ];
+                let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.h;
+
+                // W[t..t+2] + K[t..t+2] を求めてスカラーで取り出す
+                macro_rules! wk {
+                    ($t:expr) => {{
+                        let k = _mm_loadu_si128(K.as_ptr().add($t) as *const __m128i);
+                        let mut wk = [0u64; 2];
+                        _mm_storeu_si128(wk.as_mut_ptr() as *mut __m128i, _mm_add_epi64(w[($t / 2) % 8], k));
+                        wk
+                    }};
+                }
+
+                // W[t..t+2] を使い終えた位置に W[t+16..t+18] を計算する（j = (t / 2) % 8）
+                macro_rules! schedule_at {
+                    ($j:expr) => {
+                        let j = $j;
+                        w[j] = schedule!(w[j], w[(j + 1) % 8], w[(j + 4) % 8], w[(j + 5) % 8], w[(j + 7) % 8]);
+                    };
+                }
+
+                // 8ラウンド分（作業変数の並びが元に戻る）
+                macro_rules! round8 {
+                    ($wk0:ident, $wk1:ident, $wk2:ident, $wk3:ident) => {
+                        round!(a, b, c, d, e, f, g, h, $wk0[0]);
+                        round!(h, a, b, c, d, e, f, g, $wk0[1]);
+                        round!(g, h, a, b, c, d, e, f, $wk1[0]);
+                        round!(f, g, h, a, b, c, d, e, $wk1[1]);
+                        round!(e, f, g, h, a, b, c, d, $wk2[0]);
+                        round!(d, e, f, g, h, a, b, c, $wk2[1]);
+                        round!(c, d, e, f, g, h, a, b, $wk3[0]);
+                        round!(b, c, d, e, f, g, h, a, $wk3[1]);
+                    };
+                }
+
+                // ラウンド t〜t+7（schedule を指定した場合は、使い終えた W[t..t+8] の位置に
+                // W[t+16..t+24] を計算し、ラウンドと並行して実行させる）
+                macro_rules! rounds8 {
+                    ($t:literal) => {
+                        let (wk0, wk1, wk2, wk3) = (wk!($t), wk!($t + 2), wk!($t + 4), wk!($t + 6));
+                        round8!(wk0, wk1, wk2, wk3);
+                    };
+                    ($t:literal, schedule) => {
+                        let (wk0, wk1, wk2, wk3) = (wk!($t), wk!($t + 2), wk!($t + 4), wk!($t + 6));
+                        schedule_at!(($t / 2) % 8);
+                        schedule_at!(($t / 2 + 1) % 8);
+                        schedule_at!(($t / 2 + 2) % 8);
+                        schedule_at!(($t / 2 + 3) % 8);
+                        round8!(wk0, wk1, wk2, wk3);
+                    };
+                }
+
+                rounds8!(0, schedule);
+                rounds8!(8, schedule);
+                rounds8!(16, schedule);
+                rounds8!(24, schedule);
+                rounds8!(32, schedule);
+                rounds8!(40, schedule);
+                rounds8!(48, schedule);
+                rounds8!(56, schedule);
+                rounds8!(64);
+                rounds8!(72);
+
+                for (s, v) in state.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                    *s = s.wrapping_add(v);
+                }
+            }
+        }
+    };
+}
+
+compress_fn!(compress_ssse3, "ssse3");
+compress_fn!(compress_avx, "avx");
+
+/// SSSE3でメッセージスケジュールを計算するバックエンド
+pub struct Ssse3;
+
+impl BlockCompressor for Ssse3 {
+    fn name(&self) -> &'static str {
+        "x86-ssse3"
+    }
+
+    fn is_available(&self) -> bool {
+        is_x86_feature_detected!("ssse3")
+    }
+
+    fn compress(&self, state: &mut Sha512State, blocks: &[[u8; 128]]) {
+        assert!(self.is_available(), "x86-ssse3: CPU does not support SSSE3");
+        // SAFETY: SSSE3が利用できることを直前に確認している
+        unsafe { compress_ssse3(state, blocks) }
+    }
+}
+
+/// AVX（VEX符号化）でメッセージスケジュールを計算するバックエンド
+pub struct Avx;
+
+impl BlockCompressor for Avx {
+    fn name(&self) -> &'static str {
+        "x86-avx"
+    }
+
+    fn is_available(&self) -> bool {
+        is_x86_feature_detected!("avx")
+    }
+
+    fn compress(&self, state: &mut Sha512State, blocks: &[[u8; 128]]) {
+        assert!(self.is_available(), "x86-avx: CPU does not support AVX");
+        // SAFETY: AVXが利用できることを直前に確認している（AVXはSSSE3を含む）
+        unsafe { compress_avx(state, blocks) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_ssse3() {
+        check_backend(&Ssse3);
+    }
+
+    #[test]
+    fn test_avx() {
+        check_backend(&Avx);
+    }
+}
@@ -0,0 +1,121 @@
+// 16語の循環メッセージスケジュールと展開済みラウンドによる汎用実装
+//
+// `sha512_transform_generic` は80語の W を確保し、インデックス付きのループで K を参照しますが、
+// こちらは W[t] を W[t-16] と同じ位置に上書きする16語の循環バッファだけを使用します。
+// 80ラウンドはマクロですべて展開し、K の添字も定数になります。作業変数は8変数の入れ替えを行わず、
+// ラウンドごとに引数の並びをずらす（レジスタリネーミング）ことで、d と h の2変数だけを更新します。
+// CPU固有命令を使用しないため、すべての環境で利用できます。
+// CPU固有命令を持たない環境では、汎用実装より優先して自動選択されます（SHA-256 と同じ方針）。
+
+use crate::backend::BlockCompressor;
+use crate::{Sha512State, K};
+
+/// 1ラウンド分の処理（d と h だけを更新し、残りは呼び出し側で名前をずらす）
+/// Ch と Maj は汎用実装と等価で演算数の少ない形で計算する
+macro_rules! round {
+    ($a:ident, $b:ident, $c:ident, $d:ident, $e:ident, $f:ident, $g:ident, $h:ident, $wk:expr) => {
+        let s1 = $e.rotate_right(14) ^ $e.rotate_right(18) ^ $e.rotate_right(41);
+        let ch = $g ^ ($e & ($f ^ $g));
+        let temp1 = $h.wrapping_add(s1).wrapping_add(ch).wrapping_add($wk);
+        let s0 = $a.rotate_right(28) ^ $a.rotate_right(34) ^ $a.rotate_right(39);
+        let maj = ($a & $b) | ($c & ($a | $b));
+        $d = $d.wrapping_add(temp1);
+        $h = temp1.wrapping_add(s0.wrapping_add(maj));
+    };
+}
+
+// W[t] + K[t] をSIMDで求める SSSE3 / AVX バックエンドも同じラウンドを使用する
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) use round;
+
+/// ラウンド0〜15: 入力から読み込んだ W[t] をそのまま使用
+macro_rules! load {
+    ($w:ident, $i:expr) => {
+        $w[$i]
+    };
+}
+
+/// ラウンド16〜79: W[t] を計算し、循環バッファの W[t-16] の位置に上書き
+macro_rules! schedule {
+    ($w:ident, $i:expr) => {{
+        let w15 = $w[($i + 1) & 15];
+        let w2 = $w[($i + 14) & 15];
+        let s0 = w15.rotate_right(1) ^ w15.rotate_right(8) ^ (w15 >> 7);
+        let s1 = w2.rotate_right(19) ^ w2.rotate_right(61) ^ (w2 >> 6);
+        $w[$i] = $w[$i]
+            .wrapping_add(s0)
+            .wrapping_add($w[($i + 9) & 15])
+            .wrapping_add(s1);
+        $w[$i]
+    }};
+}
+
+/// 1ブロック分の圧縮関数
+#[inline(always)]
+fn transform(state: &mut Sha512State, block: &[u8; 128]) {
+    let (words, _) = block.as_chunks::<8>();
+    let mut w: [u64; 16] = std::array::from_fn(|i| u64::from_be_bytes(words[i]));
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.h;
+
+    // 16ラウンド分（8ラウンドごとに作業変数の並びが元に戻る）
+    macro_rules! rounds16 {
+        ($t:literal, $wt:ident) => {
+            round!(a, b, c, d, e, f, g, h, K[$t].wrapping_add($wt!(w, 0)));
+            round!(h, a, b, c, d, e, f, g, K[$t + 1].wrapping_add($wt!(w, 1)));
+            round!(g, h, a, b, c, d, e, f, K[$t + 2].wrapping_add($wt!(w, 2)));
+            round!(f, g, h, a, b, c, d, e, K[$t + 3].wrapping_add($wt!(w, 3)));
+            round!(e, f, g, h, a, b, c, d, K[$t + 4].wrapping_add($wt!(w, 4)));
+            round!(d, e, f, g, h, a, b, c, K[$t + 5].wrapping_add($wt!(w, 5)));
+            round!(c, d, e, f, g, h, a, b, K[$t + 6].wrapping_add($wt!(w, 6)));
+            round!(b, c, d, e, f, g, h, a, K[$t + 7].wrapping_add($wt!(w, 7)));
+            round!(a, b, c, d, e, f, g, h, K[$t + 8].wrapping_add($wt!(w, 8)));
+            round!(h, a, b, c, d, e, f, g, K[$t + 9].wrapping_add($wt!(w, 9)));
+            round!(g, h, a, b, c, d, e, f, K[$t + 10].wrapping_add($wt!(w, 10)));
+            round!(f, g, h, a, b, c, d, e, K[$t + 11].wrapping_add($wt!(w, 11)));
+            round!(e, f, g, h, a, b, c, d, K[$t + 12].wrapping_add($wt!(w, 12)));
+            round!(d, e, f, g, h, a, b, c, K[$t + 13].wrapping_add($wt!(w, 13)));
+            round!(c, d, e, f, g, h, a, b, K[$t + 14].wrapping_add($wt!(w, 14)));
+            round!(b, c, d, e, f, g, h, a, K[$t + 15].wrapping_add($wt!(w, 15)));
+        };
+    }
+
+    rounds16!(0, load);
+    rounds16!(16, schedule);
+    rounds16!(32, schedule);
+    rounds16!(48, schedule);
+    rounds16!(64, schedule);
+
+    for (s, v) in state.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// 循環メッセージスケジュールと展開済みラウンドを使用する汎用実装（すべての環境で利用可能）
+pub struct Unrolled;
+
+impl BlockCompressor for Unrolled {
+    fn name(&self) -> &'static str {
+        "unrolled"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn compress(&self, state: &mut Sha512State, blocks: &[[u8; 128]]) {
+        for block in blocks {
+            transform(state, block);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::testing::check_backend;
+
+    #[test]
+    fn test_unrolled() {
+        check_backend(&Unrolled);
+    }
+}